@@ -0,0 +1,168 @@
+use crate::active_util::*;
+use crate::game_state::*;
+use crate::util::*;
+use bevy::prelude::*;
+use bevy::sprite::collide_aabb::collide;
+
+//abilities are unlocked once and then always on, unlike items they are never equipped or swapped with J
+pub const WALL_JUMP: &str = "WallJump";
+pub const ABILITIES: [&str; 1] = [WALL_JUMP];
+
+//dashes the player gets back on landing, and ticks between dashes
+const DASH_CHARGES: u8 = 1;
+const DASH_COOLDOWN: u32 = 30;
+//how long and how fast a dash goes, it holds the player's height the whole way
+const DASH_TICKS: u32 = 8;
+const DASH_SPEED: f32 = 15.;
+//ticks enemy melee and projectiles pass through the player after starting a dash
+const DASH_I_FRAMES: u32 = 12;
+//how long a trail sprite takes to fade, in seconds
+const TRAIL_FADE: f32 = 0.2;
+
+//the fastest the player falls while pressed into a wall
+const WALL_SLIDE_SPEED: f32 = -2.;
+//how hard a wall jump pushes away from the wall and up
+const WALL_JUMP_PUSH: f32 = 7.5;
+const WALL_JUMP_SPEED: f32 = 10.;
+
+pub fn find_ability(name: &str) -> Option<&'static str> {
+    ABILITIES.iter().copied().find(|a| *a == name)
+}
+
+//abilities in a list of unlocks, for starting a run or loading a save
+pub fn unlocked_abilities(unlocks: &[String]) -> Vec<&'static str> {
+    unlocks.iter().filter_map(|u| find_ability(u)).collect()
+}
+
+//slows the fall of anything pressed into a wall in the air
+pub fn wall_slide(obj: &mut ActiveObject) {
+    if obj.velocity.y < WALL_SLIDE_SPEED {
+        obj.velocity.y = WALL_SLIDE_SPEED;
+    }
+}
+
+//kicks off the wall being pressed into, this also gives back any air jumps
+pub fn wall_jump(obj: &mut ActiveObject) {
+    obj.velocity.x = -obj.wall * WALL_JUMP_PUSH;
+    obj.velocity.y = WALL_JUMP_SPEED;
+    obj.facing_left = obj.wall > 0.;
+    obj.air_jumps = 0;
+}
+
+//the player's dash, started with Left Shift
+pub struct Dash {
+    pub charges: u8,
+    pub cooldown: u32,
+    //ticks left in the current dash, and which way it goes
+    pub ticks: u32,
+    pub dir: f32,
+    pub i_frames: u32,
+}
+
+impl Dash {
+    pub fn new() -> Self {
+        Self {
+            charges: DASH_CHARGES,
+            cooldown: 0,
+            ticks: 0,
+            dir: 1.,
+            i_frames: 0,
+        }
+    }
+    pub fn active(&self) -> bool {
+        self.ticks > 0
+    }
+    pub fn invulnerable(&self) -> bool {
+        self.i_frames > 0
+    }
+    //counts everything down, landing gives the charges back
+    pub fn tick(&mut self, obj: &mut ActiveObject) {
+        if obj.grounded {
+            self.charges = DASH_CHARGES;
+        }
+        self.cooldown = self.cooldown.saturating_sub(1);
+        self.i_frames = self.i_frames.saturating_sub(1);
+        if self.ticks > 0 {
+            self.ticks -= 1;
+            if self.ticks == 0 {
+                //back to walking speed, otherwise holding a direction would keep the dash going
+                obj.velocity.x = obj.velocity.x.clamp(-PLAYER_SPEED, PLAYER_SPEED);
+            }
+        }
+    }
+    pub fn start(&mut self, obj: &ActiveObject) {
+        if self.active() || self.cooldown > 0 || self.charges == 0 {
+            return;
+        }
+        self.charges -= 1;
+        self.cooldown = DASH_COOLDOWN;
+        self.ticks = DASH_TICKS;
+        self.i_frames = DASH_I_FRAMES;
+        self.dir = if obj.facing_left { -1. } else { 1. };
+    }
+    //moves the player for one tick of the dash, ignoring gravity and their item
+    pub fn apply(&self, obj: &mut ActiveObject) {
+        obj.velocity.x = self.dir * DASH_SPEED;
+        obj.velocity.y = 0.;
+    }
+}
+
+//faded copies of the player left behind while dashing
+#[derive(Component)]
+pub struct DashTrail {
+    fade: Timer,
+}
+
+pub fn draw_dash_trail(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Query<(&Transform, &Player)>,
+    mut trail: Query<(Entity, &mut Sprite, &mut DashTrail)>,
+) {
+    for (e, mut sprite, mut t) in trail.iter_mut() {
+        t.fade.tick(time.delta());
+        if t.fade.finished() {
+            commands.entity(e).despawn();
+        } else {
+            sprite.color.set_a(0.5 * t.fade.percent_left());
+        }
+    }
+    let (pt, p) = player.single();
+    if p.dash.active() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.6, 0.8, 1., 0.5),
+                    custom_size: Some(Vec2::splat(PLAYER_SZ)),
+                    ..default()
+                },
+                transform: Transform::from_translation(pt.translation - Vec3::Z),
+                ..default()
+            })
+            .insert(DashTrail {
+                fade: Timer::from_seconds(TRAIL_FADE, false),
+            });
+    }
+}
+
+pub fn collect_ability_pickups(
+    mut commands: Commands,
+    mut player: Query<(&Transform, &Object, &mut Player)>,
+    pickups: Query<(Entity, &Transform, &Object), Without<Player>>,
+    mut progress: ResMut<Progress>,
+) {
+    let (pt, po, mut p) = player.single_mut();
+    for (e, t, o) in pickups.iter() {
+        if !matches!(o.obj_type, ObjectType::WallJumpPickup) {
+            continue;
+        }
+        if collide(pt.translation, Vec2::new(po.width, po.height), t.translation, Vec2::new(o.width, o.height)).is_some() {
+            commands.entity(e).despawn();
+            if !p.abilities.contains(&WALL_JUMP) {
+                p.abilities.push(WALL_JUMP);
+                println!("Wall jump unlocked! Jump while pressed into a wall to kick off it");
+            }
+            progress.unlock(WALL_JUMP);
+        }
+    }
+}
@@ -1,10 +1,20 @@
+use crate::abilities::*;
+use crate::items::grappling_hook::*;
+use crate::items::jetpack::*;
+use crate::items::no_item::*;
+use crate::items::*;
+use crate::melee::*;
 use crate::util::*;
+use crate::weapons::*;
 use bevy::prelude::*;
+use std::time::Duration;
 
 pub const PLAYER_SPEED: f32 = 5.;
 pub const PLAYER_SZ: f32 = 32.;
 
 pub const ENEMY_HEALTH: i32 = 100;
+//how long one way platforms are ignored after dropping through one
+pub const DROP_FRAMES: u32 = 10;
 
 #[derive(Component)]
 pub struct ClockText;
@@ -15,15 +25,32 @@ pub struct Clock {
     pub timer: Timer,
 }
 
-/*
-impl Clock{
-    pub fn new(t: Timer) -> Self {
+impl Clock {
+    pub fn new(seconds: f32) -> Self {
         Self {
-            timer: t,
-         }
+            timer: Timer::from_seconds(seconds, true),
+        }
+    }
+    pub fn remaining(&self) -> f32 {
+        self.timer.duration().as_secs_f32() - self.timer.elapsed_secs()
+    }
+    //adds to the time left, going over what the clock started with if needed
+    pub fn add_time(&mut self, seconds: f32) {
+        let elapsed = self.timer.elapsed_secs();
+        if seconds <= elapsed {
+            self.timer.set_elapsed(Duration::from_secs_f32(elapsed - seconds));
+        } else {
+            let duration = self.timer.duration().as_secs_f32() + seconds - elapsed;
+            self.timer.set_duration(Duration::from_secs_f32(duration));
+            self.timer.set_elapsed(Duration::ZERO);
+        }
+    }
+    //starts over with `seconds` left
+    pub fn restart(&mut self, seconds: f32) {
+        self.timer.set_duration(Duration::from_secs_f32(seconds));
+        self.timer.reset();
     }
 }
-*/
 
 #[derive(Component)]
 pub struct HealthBar;
@@ -56,40 +83,52 @@ pub struct ActiveObject {
     pub grounded: bool,
     pub facing_left: bool,
     pub velocity: Vec2,
-    pub max_health: i32,
-    pub health: i32,
     pub projected_position: Vec3,
     pub stuck: bool,
+    pub on_one_way: bool,
+    pub drop_frames: u32,
+    pub on_slope: bool,
+    pub slope: f32,
+    pub friction: f32,
+    //jumps taken since last leaving the ground, see items/double_jump.rs
+    pub air_jumps: u8,
+    //side of the wall being pushed into, -1 on the left, 1 on the right and 0 when not touching one
+    pub wall: f32,
 }
 
 impl ActiveObject {
-    pub fn new(h: i32, d: i32) -> Self {
+    pub fn new() -> Self {
         Self {
             grounded: false,
             facing_left: false,
             velocity: Vec2::splat(0.),
-            max_health: h,
-            health: h,
             projected_position: Vec3::splat(0.),
             stuck: false,
+            on_one_way: false,
+            drop_frames: 0,
+            on_slope: false,
+            slope: 0.,
+            friction: 1.,
+            air_jumps: 0,
+            wall: 0.,
         }
     }
 }
-#[derive(PartialEq)]
-pub enum ItemType {
-    None,
-    Jetpack,
-    Umbrella,
-    Boots,
-}
-
 #[derive(Component)]
 pub struct Player {
     pub credits: i32,
-    pub items: Vec<ItemType>,
+    //names of the items the player owns, see items/mod.rs
+    pub items: Vec<&'static str>,
+    //unlocked abilities, see abilities.rs
+    pub abilities: Vec<&'static str>,
     pub active_item: usize,
-    pub health: i8,
     pub healing_bar: i32,
+    pub dash: Dash,
+    pub fuel: Fuel,
+    pub arsenal: Arsenal,
+    pub combo: Combo,
+    //set while the grappling hook is holding on to something
+    pub rope: Option<Rope>,
     //temp variable
 }
 
@@ -97,23 +136,51 @@ impl Player {
     pub fn new() -> Self {
         Self {
             credits: 100,
-            items: vec![ItemType::None],
+            items: vec![NO_ITEM],
+            abilities: Vec::new(),
             active_item: 0,
-            health: 100,
             healing_bar: 0,
+            dash: Dash::new(),
+            fuel: Fuel::new(0),
+            arsenal: Arsenal::new(),
+            combo: Combo::new(),
+            rope: None,
         }
     }
+    pub fn has_ability(&self, name: &str) -> bool {
+        self.abilities.iter().any(|a| *a == name)
+    }
+    pub fn equipped(&self) -> &'static dyn Item {
+        find_item(self.items[self.active_item]).unwrap_or(&NoItem)
+    }
 }
 
 #[derive(Component)]
 pub struct Hitbox {
     // lifespan: Timer,
+    pub size: Vec2,
+    pub damage: i32,
+    pub knockback: Vec2,
+    pub stun: u32,
+    //down swings bounce the player off whatever they hit
+    pub pogo: bool,
 }
 
 impl Hitbox {
-    pub fn new() -> Self {
+    //a swing of `step` toward `dir`, which is one of left, right, up or down
+    pub fn new(step: &ComboStep, dir: Vec2) -> Self {
+        let sideways = dir.y == 0.;
         Self { 
             // lifespan: lifespan
+            size: if sideways { step.size } else { Vec2::new(step.size.y, step.size.x) },
+            damage: step.damage,
+            knockback: if sideways {
+                Vec2::new(dir.x * step.knockback.x, step.knockback.y)
+            } else {
+                Vec2::new(0., dir.y * step.knockback.x)
+            },
+            stun: step.stun,
+            pogo: dir.y < 0.,
          }
     }
 }
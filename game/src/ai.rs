@@ -91,10 +91,21 @@ pub struct Enemy{
     pub assist_possible: bool,
     pub friend: Vec2,
     pub static_retreat_frames: usize,
+    pub tick: u32,
+    pub waiting: bool,
+    //health the enemy spawned with, and below which it retreats instead of fighting
+    pub max_health: i32,
+    pub retreat_health: i32,
+    //whether the enemy can take DoubleJump edges
+    pub double_jump: bool,
+    //whether the enemy can take WallJumpLeft and WallJumpRight edges
+    pub wall_jump: bool,
 }
 
 impl Enemy{
-    pub fn new(ty: Type) -> Self {
+    pub fn new(ty: Type, max_health: i32, retreat_health: i32) -> Self {
+        //melee and hybrid enemies chase the player up walls, ranged ones keep their distance
+        let wall_jump = matches!(ty, Type::Melee | Type::Hybrid);
         Self{
             t: ty,
             //supgraph of the movement mesh that has been seen by enemy
@@ -119,9 +130,22 @@ impl Enemy{
             assist_possible: false,
             friend: Vec2::splat(f32::MAX),
             static_retreat_frames: 0, //this shouldn't be needed but it's too late in the game to avoid it
+            //current platform clock tick, and whether the enemy is waiting on a moving platform to arrive
+            tick: 0,
+            waiting: false,
+            max_health: max_health,
+            retreat_health: retreat_health,
+            double_jump: false,
+            wall_jump: wall_jump,
         }
     }
-    pub fn decide_motion(&mut self, pos: Vec2, health: i32)-> Motion{
+    //a melee hit interrupts whatever the enemy was doing
+    pub fn interrupt(&mut self){
+        self.attack = Attack::None;
+        self.motion = Motion::Stop;
+        self.waiting = false;
+    }
+    pub fn decide_motion(&mut self, pos: Vec2, health: i32, rng: &mut impl Rng)-> Motion{
         //only update motion if enemy has seen at least one vertex
         self.attack = Attack::None;
         self.recover_health = false;
@@ -129,12 +153,14 @@ impl Enemy{
         if self.enemy_graph.vertices.len() > 0 {
             let x_dist = (self.player_pos.x - pos.x).abs();
             let y_dist = self.player_pos.y - pos.y;
-            if pos == self.old_pos && !matches!(self.action, Action::Attack){
+            //waiting for or riding a moving platform doesn't count as being stuck
+            let on_platform = self.waiting || matches!(self.motion, Motion::Ride);
+            if pos == self.old_pos && !matches!(self.action, Action::Attack) && !on_platform{
                 self.immobile_frames += 1;
                 self.static_retreat_frames += 1;
             }
             //first check is for if player should be attacked
-            if self.player_seen && x_dist < 100. && y_dist < 100. && health >= ENEMY_HEALTH/2{
+            if self.player_seen && x_dist < 100. && y_dist < 100. && health >= self.retreat_health{
                 if (matches!(self.t, Type::Ranged))&& x_dist < 50.{
                     self.action = Action::Retreat;
                 }
@@ -155,7 +181,7 @@ impl Enemy{
                 self.action = Action::Attack;
             }
             //catch all cases where retreat is better than run or reset
-            else if !matches!(self.t, Type::Hybrid) && self.player_seen && health < ENEMY_HEALTH/2 && (matches!(self.action, Action::Attack) || !matches!(self.action, Action::Heal)) && x_dist < 150. && y_dist < 100.{
+            else if !matches!(self.t, Type::Hybrid) && self.player_seen && health < self.retreat_health && (matches!(self.action, Action::Attack) || !matches!(self.action, Action::Heal)) && x_dist < 150. && y_dist < 100.{
                 self.action = Action::Retreat;
             }
             //if stuck, new or done attacking player, and not healing
@@ -166,14 +192,14 @@ impl Enemy{
                 self.action = Action::Reset;
             }
             else if self.player_seen {
-                if !matches!(self.t, Type::Hybrid) && health < ENEMY_HEALTH/2 && !matches!(self.action, Action::Reset){
+                if !matches!(self.t, Type::Hybrid) && health < self.retreat_health && !matches!(self.action, Action::Reset){
                     self.action = Action::Run;
                 }
                 else{
                     self.action = Action::Chase;
                 }
             }
-            else if health < ENEMY_HEALTH{
+            else if health < self.max_health{
                 self.action = Action::Heal;
             }
             else if self.assist_possible{
@@ -198,14 +224,23 @@ impl Enemy{
             else{
                 self.action = Action::Strafe;
             }
-            self.update_motion(pos, health);
+            self.update_motion(pos, health, rng);
         }
         self.old_pos = pos;
         return self.motion;
     }
 
      //updates enemy motion type if they are at or 
-     fn update_motion(&mut self, pos: Vec2, health: i32){
+     fn update_motion(&mut self, pos: Vec2, health: i32, rng: &mut impl Rng){
+
+        //enemy is waiting on a timed edge, check if the platform is there yet
+        if self.waiting && matches!(self.action, Action::Strafe | Action::Run | Action::Chase | Action::Assist){
+            self.motion = self.edge_motion(self.current_vertex, self.next_vertex);
+            if self.waiting{
+                return;
+            }
+        }
+        self.waiting = false;
 
         match self.action{
             Action::Reset => {
@@ -259,7 +294,6 @@ impl Enemy{
                         if self.current_vertex == self.target_vertex{
                             //randomly select a seen vertex
                             let r = self.enemy_graph.vertices.len();
-                            let mut rng = rand::thread_rng();
 
                             let pos: usize = rng.gen_range(0, r);
                             self.target_vertex = self.enemy_graph.vertices[pos].id;
@@ -273,7 +307,7 @@ impl Enemy{
                         }
                         if self.path.vertices.len() > self.index_in_path{
                             self.next_vertex = self.path.vertices[self.index_in_path];
-                            self.motion = self.enemy_graph.edges[self.current_vertex][self.next_vertex].path; 
+                            self.motion = self.edge_motion(self.current_vertex, self.next_vertex);
                         }
                         //maybe bug here idk
                     }
@@ -312,7 +346,7 @@ impl Enemy{
                         }
                         if self.path.vertices.len() > self.index_in_path{
                             self.next_vertex = self.path.vertices[self.index_in_path];
-                            self.motion = self.enemy_graph.edges[self.current_vertex][self.next_vertex].path; 
+                            self.motion = self.edge_motion(self.current_vertex, self.next_vertex);
                         }
                         //maybe bug here idk
                     }
@@ -371,7 +405,7 @@ impl Enemy{
                         }
                         if self.path.vertices.len() > self.index_in_path{
                             self.next_vertex = self.path.vertices[self.index_in_path];
-                            self.motion = self.enemy_graph.edges[self.current_vertex][self.next_vertex].path; 
+                            self.motion = self.edge_motion(self.current_vertex, self.next_vertex);
                         }
                     }
                     else {
@@ -442,7 +476,7 @@ impl Enemy{
                         }
                     }
                     Type::Hybrid => {
-                        if health > ENEMY_HEALTH / 2{
+                        if health > self.retreat_health{
                             if x_to_player.abs() <= PLAYER_SZ{
                                 if y_to_player.abs() <= PLAYER_SZ{
                                     //within range to attack
@@ -538,7 +572,7 @@ impl Enemy{
                         }
                         if self.path.vertices.len() > self.index_in_path{
                             self.next_vertex = self.path.vertices[self.index_in_path];
-                            self.motion = self.enemy_graph.edges[self.current_vertex][self.next_vertex].path; 
+                            self.motion = self.edge_motion(self.current_vertex, self.next_vertex);
                         }
                     }
                     else {
@@ -551,6 +585,21 @@ impl Enemy{
     }
 
 
+    //motion needed to take an edge, edges onto or off of moving platforms make the enemy wait until they can be used
+    fn edge_motion(&mut self, from: usize, to: usize) -> Motion{
+        let edge = self.enemy_graph.edges[from][to];
+        match edge.window{
+            Some(w) if !w.contains(self.tick) => {
+                self.waiting = true;
+                Motion::Stop
+            }
+            _ => {
+                self.waiting = false;
+                edge.path
+            }
+        }
+    }
+
     fn nearest_vert(&self, pos: Vec2) -> usize{
         
         let mut distance = f32::MAX;
@@ -607,8 +656,11 @@ impl Enemy{
             //let mut prev: usize = 0;
             for edge in &self.enemy_graph.edges[position] {
                 match edge.path {
-                    Motion::Left | Motion::Right | Motion::Jump |
-                    Motion::JumpRight | Motion::JumpLeft | Motion::Fall => {
+                    Motion::DoubleJump if !self.double_jump => {}
+                    Motion::WallJumpLeft | Motion::WallJumpRight if !self.wall_jump => {}
+                    Motion::Left | Motion::Right | Motion::Jump | Motion::DoubleJump |
+                    Motion::WallJumpLeft | Motion::WallJumpRight |
+                    Motion::JumpRight | Motion::JumpLeft | Motion::Fall | Motion::Ride => {
 
                         //replace 1 with edge weight
                         let next = State { cost: cost + 1, position: index };
@@ -0,0 +1,479 @@
+use crate::active_util::*;
+use crate::ai::*;
+use crate::game_state::*;
+use crate::physics::*;
+use crate::status_effects::*;
+use crate::util::*;
+use bevy::prelude::*;
+use bevy::sprite::collide_aabb::Collision;
+
+//we will also need to implement collisions between 2 active objects, that is where we will do rigidbody collisions
+//I'm not sure whether that should run before or after object collisions
+pub fn apply_collisions(
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut actives: Query<(&mut Object, &mut ActiveObject, &Transform, Option<&mut StatusEffects>), With<ActiveObject>>,
+    mut objects: Query<(&mut Object, &Transform), (With<Object>, Without<ActiveObject>)>,
+    mut m: Query<&mut Manager, (With<Manager>)>,
+    //input: Res<Input<KeyCode>>,
+) {
+    //loop through all objects that move
+    let mut manager = m.single_mut();
+    for (mut object, mut active, transform, mut effects) in actives.iter_mut() {
+        let was_on_slope = active.on_slope;
+        active.on_slope = false;
+        //friction comes from whatever surface gets landed on this tick, otherwise it's normal
+        active.friction = 1.;
+        active.wall = 0.;
+        for (mut o, t) in objects.iter_mut() {
+            //slopes are collided with by following their surface instead of as a box
+            if let Some(ratio) = slope_ratio(o.obj_type) {
+                let x = active.projected_position.x;
+                if let Some(surface) = slope_surface(o.obj_type, o.width, o.height, t.translation, x, object.width / 2.) {
+                    let feet = active.projected_position.y - object.height / 2.;
+                    let prev_feet = transform.translation.y - object.height / 2.;
+                    let step = active.velocity.x.abs() * ratio.abs() + 2.;
+                    //stick to the slope when walking down it instead of bouncing off
+                    let snap = if was_on_slope { step } else { 0. };
+                    if active.velocity.y <= 0. && feet <= surface + snap && prev_feet >= surface - step {
+                        active.projected_position.y = surface + object.height / 2.;
+                        land_on(&mut active, &surface_material(o.obj_type));
+                        active.on_slope = true;
+                        active.slope = ratio;
+                        continue;
+                    }
+                }
+                //the tall side and the flat underside of a slope are a wall and a ceiling, like a block's
+                let res = bevy::sprite::collide_aabb::collide(
+                    active.projected_position,
+                    Vec2::new(object.width, object.height),
+                    t.translation,
+                    Vec2::new(o.width, o.height),
+                );
+                let below_top = active.projected_position.y - object.height / 2. < t.translation.y + o.height / 2. - 1.;
+                match res {
+                    Some(Collision::Left) if ratio < 0. && below_top => {
+                        active.velocity.x = 0.;
+                        active.wall = 1.;
+                        active.projected_position.x = t.translation.x - (o.width / 2.) - object.width / 2.;
+                    }
+                    Some(Collision::Right) if ratio > 0. && below_top => {
+                        active.velocity.x = 0.;
+                        active.wall = -1.;
+                        active.projected_position.x = t.translation.x + (o.width / 2.) + object.width / 2.;
+                    }
+                    Some(Collision::Bottom) => {
+                        active.velocity.y = 0.;
+                        active.projected_position.y = t.translation.y - (o.height / 2.) - object.height / 2.;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            let res = bevy::sprite::collide_aabb::collide(
+                active.projected_position,
+                //need to change this to get the size of whatever the object is
+                Vec2::new(object.width, object.height),
+                t.translation,
+                Vec2::new(o.width, o.height),
+            );
+            if res.is_some() {
+                let coll_type: bevy::sprite::collide_aabb::Collision = res.unwrap();
+                let mat = surface_material(o.obj_type);
+
+                //things like cobwebs slow down whatever passes through them from any side
+                if !mat.solid && (mat.drag > 0. || mat.sink.is_some()) {
+                    drag_through(&mut active, &mat);
+                    //and keep them slowed for a moment after
+                    if let (Some(effect), Some(effects)) = (mat.inflicts, effects.as_mut()) {
+                        effects.keep(effect);
+                    }
+                    continue;
+                }
+
+                //walking off a slope onto a block level with its top steps up onto it instead of hitting its side
+                let feet = active.projected_position.y - object.height / 2.;
+                let top = t.translation.y + (o.height / 2.);
+                if was_on_slope
+                    && mat.solid
+                    && matches!(coll_type, Collision::Left | Collision::Right)
+                    && !matches!(o.obj_type, ObjectType::OneWayPlatform | ObjectType::Teleporter)
+                    && top - feet <= active.velocity.x.abs() + 2.
+                {
+                    active.projected_position.y = top + object.height / 2.;
+                    land_on(&mut active, &mat);
+                    continue;
+                }
+
+                match coll_type {
+                    Collision::Left => match o.obj_type {
+                        ObjectType::Teleporter => {
+                            manager.go_to(o.level, o.spawn);
+                        }
+                        ObjectType::OneWayPlatform => {}
+                        _ if mat.solid => {
+                            active.velocity.x = 0.;
+                            active.wall = 1.;
+                            active.projected_position.x =
+                                t.translation.x - (o.width / 2.) - object.width / 2.;
+                            if matches!(object.obj_type, ObjectType::Barrel)
+                                || matches!(object.obj_type, ObjectType::Breakable)
+                            {
+                                active.stuck = true;
+                            }
+                        }
+                        _ => {}
+                    },
+                    Collision::Right => match o.obj_type {
+                        ObjectType::Teleporter => {
+                            manager.go_to(o.level, o.spawn);
+                        }
+                        ObjectType::OneWayPlatform => {}
+                        _ if mat.solid => {
+                            active.velocity.x = 0.;
+                            active.wall = -1.;
+                            active.projected_position.x =
+                                t.translation.x + (o.width / 2.) + object.width / 2.;
+                            if matches!(object.obj_type, ObjectType::Barrel)
+                                || matches!(object.obj_type, ObjectType::Breakable)
+                            {
+                                active.stuck = true;
+                            }
+                        }
+                        _ => {}
+                    },
+                    Collision::Top => {
+                        if matches!(object.obj_type, ObjectType::Barrel)
+                            || matches!(object.obj_type, ObjectType::Breakable)
+                        {
+                            if (!active.grounded && active.velocity.y < -15.) {
+                                object.broken = true;
+                            }
+                        }
+                        match o.obj_type {
+                            //one way platforms only catch things falling onto them from above
+                            ObjectType::OneWayPlatform => {
+                                let feet = transform.translation.y - object.height / 2.;
+                                let top = t.translation.y + o.height / 2.;
+                                if active.velocity.y <= 0. && active.drop_frames == 0 && feet >= top - 1. {
+                                    active.projected_position.y = top + object.height / 2.;
+                                    land_on(&mut active, &mat);
+                                    active.on_one_way = true;
+                                }
+                            }
+                            //riders on moving platforms were already moved along with it in move_platforms
+                            _ if mat.solid => {
+                                active.projected_position.y =
+                                    t.translation.y + (o.height / 2.) + object.height / 2.;
+                                land_on(&mut active, &mat);
+                                if let (Some(effect), Some(effects)) = (mat.inflicts, effects.as_mut()) {
+                                    effects.add(effect);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    Collision::Bottom => match o.obj_type {
+                        ObjectType::OneWayPlatform => {}
+                        _ if mat.solid => {
+                            active.velocity.y = 0.;
+                            active.projected_position.y =
+                                t.translation.y - (o.height / 2.) - object.height / 2.;
+                        }
+                        _ => {}
+                    },
+                    Collision::Inside => match o.obj_type {
+                        ObjectType::OneWayPlatform => {}
+                        _ => {
+                            active.velocity = Vec2::new(0., 0.);
+                        }
+                    },
+                }
+            } else if active.velocity.y <= 0.
+                && resting_on(active.projected_position, Vec2::new(object.width, object.height), t.translation, Vec2::new(o.width, o.height))
+            {
+                //standing still on top of something doesn't overlap it, but it still holds up whatever is on it
+                let mat = surface_material(o.obj_type);
+                match o.obj_type {
+                    ObjectType::OneWayPlatform if active.drop_frames == 0 => {
+                        land_on(&mut active, &mat);
+                        active.on_one_way = true;
+                    }
+                    ObjectType::OneWayPlatform => {}
+                    _ if mat.solid => land_on(&mut active, &mat),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+//standing on a surface: stop falling (or bounce), and pick up how slippery or sticky it is
+fn land_on(active: &mut ActiveObject, mat: &SurfaceMaterial) {
+    if active.velocity.y < 0. {
+        active.velocity.y = (-active.velocity.y * mat.bounciness).max(mat.launch);
+    }
+    active.grounded = active.velocity.y <= 0.;
+    active.friction = mat.friction;
+    active.velocity.x *= 1. - mat.drag;
+}
+
+//collide only counts boxes that overlap, this also counts a box whose bottom sits exactly on top of the other
+fn resting_on(pos: Vec3, size: Vec2, o_pos: Vec3, o_size: Vec2) -> bool {
+    let feet = pos.y - size.y / 2.;
+    let top = o_pos.y + o_size.y / 2.;
+    (feet - top).abs() < 0.01 && (pos.x - o_pos.x).abs() < (size.x + o_size.x) / 2.
+}
+
+//passing through a surface that isn't solid
+fn drag_through(active: &mut ActiveObject, mat: &SurfaceMaterial) {
+    active.velocity.x *= 1. - mat.drag;
+    if let Some(sink) = mat.sink {
+        active.velocity.y = sink;
+    }
+    active.grounded = false;
+}
+
+//this function doesn't seem to work
+pub fn enemy_collisions(
+    mut actives: Query<
+        (&mut ActiveObject, &Transform),
+        (With<Player>, Without<Enemy>, Without<MovableObject>),
+    >,
+    mut enemies: Query<
+        (&mut ActiveObject, &mut Transform),
+        (With<Enemy>, Without<Player>, Without<MovableObject>),
+    >,
+) {
+    for (mut active, transform) in actives.iter_mut() {
+        for (o, t) in enemies.iter() {
+            let res = bevy::sprite::collide_aabb::collide(
+                active.projected_position,
+                Vec2::new(PLAYER_SZ, PLAYER_SZ),
+                o.projected_position,
+                Vec2::new(PLAYER_SZ, PLAYER_SZ),
+            );
+            if res.is_some() {
+                let coll_type: bevy::sprite::collide_aabb::Collision = res.unwrap();
+                match coll_type {
+                    Collision::Left => {
+                        active.velocity.x = 0.;
+                        active.projected_position.x =
+                            t.translation.x - (PLAYER_SZ / 2.) - PLAYER_SZ / 2.;
+                    }
+                    Collision::Right => {
+                        active.velocity.x = 0.;
+                        active.projected_position.x =
+                            t.translation.x + (PLAYER_SZ / 2.) + PLAYER_SZ / 2.;
+                    }
+                    Collision::Top => {
+                        if active.velocity.y < 0. {
+                            active.velocity.y = 0.;
+                            active.grounded = true;
+                        }
+                        active.projected_position.y =
+                            t.translation.y + (PLAYER_SZ / 2.) + PLAYER_SZ / 2.;
+                    }
+                    Collision::Bottom => {
+                        active.velocity.y = 0.;
+                        active.projected_position.y =
+                            t.translation.y - (PLAYER_SZ / 2.) - PLAYER_SZ / 2.;
+                    }
+                    Collision::Inside => {
+                        active.velocity = Vec2::new(0., 0.);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn object_collisions(
+    mut movables: Query<
+        (&mut Object, &mut ActiveObject, &mut Transform),
+        (With<MovableObject>, Without<Player>, Without<Enemy>),
+    >,
+    mut player: Query<(&mut ActiveObject, &mut Transform), (With<Player>, Without<MovableObject>)>,
+    //mut objects2: Query<(&mut Object, &mut Transform), (With<Object>, Without<ActiveObject>)>,
+) {
+    let (mut pao, pt) = player.single_mut();
+    for (mut o, mut ao, mut t) in movables.iter_mut() {
+        let hit_top_half = bevy::sprite::collide_aabb::collide(
+            pao.projected_position,
+            //ne    ed to change this to get the size of whatever the object is
+            Vec2::new(PLAYER_SZ, PLAYER_SZ),
+            ao.projected_position,
+            Vec2::new(o.width, o.height),
+        );
+        if hit_top_half.is_some() {
+            //if player collides with movable object
+            let coll_type: bevy::sprite::collide_aabb::Collision = hit_top_half.unwrap();
+            match coll_type {
+                Collision::Top => {
+                    pao.velocity.y = 0.;
+                    pao.grounded = true;
+                    ao.velocity.y = 0.;
+                }
+                Collision::Left => {
+                    //t.rotate_z(-0.1);
+                    if pao.velocity.x > 0. {
+                        if (!ao.stuck) {
+                            ao.velocity.x = pao.velocity.x;
+                        }
+                    }
+                    pao.projected_position.x = t.translation.x - (PLAYER_SZ / 2.) - o.width / 2.;
+                }
+                Collision::Right => {
+                    if pao.velocity.x < 0. {
+                        if (!ao.stuck) {
+                            ao.velocity.x = pao.velocity.x;
+                        }
+                    }
+                    pao.projected_position.x = t.translation.x + (PLAYER_SZ / 2.) + o.width / 2.;
+                }
+                Collision::Bottom => {
+                    pao.velocity.y = 0.;
+                    ao.velocity.y = 0.;
+                }
+                Collision::Inside => {}
+            }
+        } else {
+            if pao.velocity.y <= 0.
+                && resting_on(pao.projected_position, Vec2::new(PLAYER_SZ, PLAYER_SZ), ao.projected_position, Vec2::new(o.width, o.height))
+            {
+                pao.grounded = true;
+            }
+            if !ao.on_slope {
+                //barrels stop when they aren't being pushed, unless the floor is slippery
+                ao.velocity.x *= 1. - ao.friction;
+            }
+        }
+    }
+}
+
+pub fn update_positions(
+    mut actives: Query<(&ActiveObject, &mut Transform), (With<ActiveObject>, Without<Player>)>,
+    mut player: Query<(&ActiveObject, &mut Transform), With<Player>>,
+) {
+    //update position of active objects based on projected position from apply_collisions()
+
+    for (o, mut t) in actives.iter_mut() {
+        t.translation = o.projected_position;
+    }
+    let (pl, mut pt) = player.single_mut();
+    pt.translation = pl.projected_position;
+}
+
+pub fn gravity_on_movables(
+    mut movables: Query<(&Object, &mut ActiveObject, &Transform), With<MovableObject>>,
+    mut objects: Query<(&Object, &mut Transform), (With<Object>, Without<ActiveObject>)>,
+) {
+    for (mut mo, mut active, mt) in movables.iter_mut() {
+        if !active.grounded {
+            active.velocity.y += GRAVITY;
+        }
+        //barrels on a slope roll down it
+        if active.on_slope {
+            active.velocity.x += GRAVITY * active.slope / (1. + active.slope * active.slope);
+        }
+
+        active.projected_position =
+            mt.translation + Vec3::new(active.velocity.x, active.velocity.y, 0.);
+        active.grounded = false;
+        active.on_one_way = false;
+    }
+}
+
+pub fn barrels_with_barrels(
+    mut movables: Query<
+        (&mut Object, &mut ActiveObject, &mut Transform),
+        (With<MovableObject>, Without<Player>, Without<Enemy>),
+    >,
+) {
+    let mut combinations = movables.iter_combinations_mut();
+    while let Some([(mut mo, mut mao, mut mt), (mut mo2, mut mao2, mut mt2)]) =
+        combinations.fetch_next()
+    {
+        // mutably access components data
+        let coll = bevy::sprite::collide_aabb::collide(
+            mao.projected_position,
+            Vec2::new(mo.width, mo.height),
+            mao2.projected_position,
+            Vec2::new(mo2.width, mo2.height),
+        );
+        if coll.is_some() {
+            let coll_type = coll.unwrap();
+            if (matches!(mo.obj_type, ObjectType::Barrel)
+                || matches!(mo.obj_type, ObjectType::Breakable))
+            {
+                match coll_type {
+                    Collision::Left => {
+                        if mao2.velocity.x != 0. {
+                            if (mao.stuck) {
+                                mao2.stuck = true;
+                                mao2.velocity.x = 0.;
+                            } else {
+                                mao.velocity = mao2.velocity;
+                                mao.projected_position.x = mao2.projected_position.x + mo2.width;
+                            }
+                        } else if mao.velocity.x != 0. {
+                            if (mao2.stuck) {
+                                mao.stuck = true;
+                                mao.velocity.x = 0.;
+                            } else {
+                                mao2.velocity = mao.velocity;
+                                mao2.projected_position.x = mao.projected_position.x - mo.width;
+                            }
+                        }
+                    }
+                    Collision::Right => {
+                        if mao2.velocity.x != 0. {
+                            if mao.stuck {
+                                mao2.stuck = true;
+
+                                mao2.velocity.x = 0.;
+                            } else {
+                                mao.velocity = mao2.velocity;
+                                mao.projected_position.x = mao2.projected_position.x + mo.width;
+                            }
+                        } else if mao.velocity.x != 0. {
+                            if (mao2.stuck) {
+                                mao2.stuck = true;
+                                mao.velocity.x = 0.;
+                            } else {
+                                mao2.velocity = mao.velocity;
+                                mao2.projected_position.x = mao.projected_position.x - mo.width;
+                            }
+                        }
+                    }
+                    Collision::Top => {
+                        mao.velocity.y = 0.;
+                        mao.grounded = true;
+                        mt.translation.y = mt2.translation.y + mo2.height / 2. + mo.height / 2.;
+                    }
+                    // Collision::Inside => {
+                    //     if (mt.translation.x < mt2.translation.x) {
+                    //         mt.translation.x = mt2.translation.x - mo.width;
+                    //     } else {
+                    //         mt.translation.x = mt2.translation.x + mo.width;
+                    //     }
+                    // }
+                    Collision::Bottom => {
+                        // if mao2.velocity.x != 0. {
+                        //     mao.velocity = mao2.velocity;
+                        //     mt.translation.x = mt2.translation.x + mo.width;
+                        // } else if mao.velocity.x != 0. {
+                        //     mao2.velocity = mao.velocity;
+                        //     mt2.translation.x = mt.translation.x + mo.width;
+                        // }
+                        //mao.velocity.y = 0.;
+                        // mao.velocity.x = 0.;
+                        mao2.velocity.y = 0.;
+                        mao2.grounded = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
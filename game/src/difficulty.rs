@@ -0,0 +1,90 @@
+use crate::active_util::*;
+use crate::fixed_step::*;
+use crate::level::*;
+
+//how hard a run is, picked on the main menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Nightmare,
+}
+
+//every number a difficulty changes, Normal is how the game was tuned
+pub struct DifficultyPreset {
+    pub enemy_health: i32,
+    //damage an enemy's melee attack does to the player
+    pub melee_damage: i8,
+    //damage the player's shots do to enemies
+    pub projectile_damage: i32,
+    //shop prices get multiplied by this
+    pub price_scale: f32,
+    //seconds on the run clock when a run starts and whenever it runs out
+    pub start_time: f32,
+    //how far enemies can see, in pixels
+    pub sight_distance: f32,
+    //enemies stop fighting and retreat below this fraction of their health
+    pub retreat_fraction: f32,
+    //enemies attack every this many ticks
+    pub attack_ticks: u64,
+}
+
+impl Difficulty {
+    pub fn preset(&self) -> DifficultyPreset {
+        match self {
+            Difficulty::Easy => DifficultyPreset {
+                enemy_health: 75,
+                melee_damage: 3,
+                projectile_damage: 35,
+                price_scale: 0.75,
+                start_time: 150.,
+                sight_distance: 600.,
+                retreat_fraction: 0.6,
+                attack_ticks: 20,
+            },
+            Difficulty::Normal => DifficultyPreset {
+                enemy_health: ENEMY_HEALTH,
+                melee_damage: 5,
+                projectile_damage: 25,
+                price_scale: 1.,
+                start_time: START_TIME,
+                sight_distance: 800.,
+                retreat_fraction: 0.5,
+                attack_ticks: ENEMY_ATTACK_TICKS,
+            },
+            Difficulty::Nightmare => DifficultyPreset {
+                enemy_health: 150,
+                melee_damage: 10,
+                projectile_damage: 20,
+                price_scale: 1.5,
+                start_time: 60.,
+                sight_distance: 1000.,
+                retreat_fraction: 0.25,
+                attack_ticks: 8,
+            },
+        }
+    }
+    pub fn start_time(&self) -> f32 {
+        self.preset().start_time
+    }
+    //what something costing `base` on Normal costs on this difficulty
+    pub fn price(&self, base: i32) -> i32 {
+        (base as f32 * self.preset().price_scale).round() as i32
+    }
+    //the difficulty after this one on the menu, wrapping around
+    pub fn next(&self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Nightmare,
+            Difficulty::Nightmare => Difficulty::Easy,
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Easy" => Some(Difficulty::Easy),
+            "Normal" => Some(Difficulty::Normal),
+            "Nightmare" => Some(Difficulty::Nightmare),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,543 @@
+use crate::abilities::*;
+use crate::active_util::*;
+use crate::ai::*;
+use crate::difficulty::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::items::double_jump::*;
+use crate::items::*;
+use crate::level::*;
+use crate::line_of_sight::*;
+use crate::melee::*;
+use crate::movement_mesh::*;
+use crate::physics::*;
+use crate::player::*;
+use crate::shop::*;
+use crate::status_effects::*;
+use crate::util::*;
+use crate::weapons::*;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+use rand::Rng;
+
+const PROJECTILE_SZ: f32 = 6.;
+//credits a dropped credit is worth
+pub const KILL_CREDITS: i32 = 50;
+
+//enemy sight, decisions and attacks
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_gameplay_system(move_enemies.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::MoveEnemies).after(GameplaySystem::SwingOnRope))
+            .add_gameplay_system(attack_enemies.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::AttackEnemies).after(GameplaySystem::ItemShop))
+            .add_gameplay_system(meleebox_collisions.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::MeleeboxCollisions).after(GameplaySystem::AttackActive).after(Step::Movement))
+            .add_gameplay_system(kill_enemies.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::KillEnemies).after(GameplaySystem::ApplyDamage).after(Step::Movement))
+            .add_gameplay_system(reward_kills.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::RewardKills).after(GameplaySystem::KillEnemies).after(Step::Movement))
+            .add_gameplay_system(collect_credits.run_in_state(GameState::Playing).label(Step::Level).label(GameplaySystem::CollectCredits).after(GameplaySystem::CollectTimePickups))
+            .add_gameplay_system(calculate_sight.run_in_state(GameState::Playing).label(Step::Sight).label(GameplaySystem::CalculateSight).after(Step::Combat));
+    }
+}
+
+//temporary code, should just apply gravity until they hit the ground, for now, enemies jump with j
+//eventually, enemy movement decisions can be implemented in a separate file, their results will determine which action they take
+//ex. for enemy in enemies, 1. calc sight 2. make decision on where to go 3. execute one of the select motion commands
+pub fn move_enemies(
+    input: Res<TickInput>,
+    platform_clock: Res<PlatformClock>,
+    mut game_rng: ResMut<GameRng>,
+    mut enemies: Query<
+        (&mut ActiveObject, &Transform, &mut Enemy, &mut Health, &StatusEffects),
+        (With<Enemy>, Without<MovableObject>),
+    >,
+) {
+    for (mut enemy, et, mut e, mut health, effects) in enemies.iter_mut() {
+        let mut change = Vec2::splat(0.);
+        //if input.pressed(KeyCode::G){ //comment out when enemy should move freely
+        e.tick = platform_clock.ticks;
+        if effects.stunned() {
+            //knocked back, the enemy drifts and falls until the stun wears off
+            enemy.velocity.x *= STUN_DRAG;
+            enemy.velocity.y += GRAVITY;
+        } else {
+            e.decide_motion(Vec2::new(et.translation.x, et.translation.y), health.current, &mut game_rng.rng);
+            if e.recover_health {
+                health.heal(5);
+            }
+            let old_velocity = enemy.velocity.x;
+
+            match e.motion {
+                Motion::Left => {
+                    enemy.velocity.x = -PLAYER_SPEED;
+                    enemy.velocity.y += GRAVITY;
+                }
+                Motion::Right => {
+                    enemy.velocity.x = PLAYER_SPEED;
+                    enemy.velocity.y += GRAVITY;
+                }
+                Motion::Jump => {
+                    if enemy.grounded {
+                        enemy.velocity.y = JUMP_SPEED;
+                        change.y = enemy.velocity.y;
+                        e.motion = Motion::Fall;
+                    } else {
+                        enemy.velocity.y += GRAVITY;
+                    }
+                }
+                Motion::JumpRight => {
+                    if enemy.grounded {
+                        enemy.velocity.y = JUMP_SPEED;
+                        change.y = enemy.velocity.y;
+                        e.motion = Motion::Right;
+                    } else {
+                        enemy.velocity.y += GRAVITY;
+                    }
+                    enemy.velocity.x = PLAYER_SPEED;
+                }
+                Motion::JumpLeft => {
+                    if enemy.grounded {
+                        enemy.velocity.y = JUMP_SPEED;
+                        change.y = enemy.velocity.y;
+
+                        e.motion = Motion::Left;
+                    } else {
+                        enemy.velocity.y += GRAVITY;
+                    }
+                    enemy.velocity.x = -PLAYER_SPEED;
+                }
+                Motion::DoubleJump => {
+                    if enemy.grounded {
+                        enemy.air_jumps = 0;
+                        enemy.velocity.y = JUMP_SPEED;
+                        change.y = enemy.velocity.y;
+                    } else if enemy.velocity.y <= 0. && air_jump(&mut enemy) {
+                        //second jump at the top of the first, then fall onto the platform
+                        e.motion = Motion::Fall;
+                    } else {
+                        enemy.velocity.y += GRAVITY;
+                    }
+                    enemy.velocity.x = 0.;
+                }
+                Motion::WallJumpLeft | Motion::WallJumpRight => {
+                    //run and jump at the wall on the far side, then kick off it at the top of the jump
+                    let away = if matches!(e.motion, Motion::WallJumpLeft) { -1. } else { 1. };
+                    if enemy.grounded {
+                        enemy.velocity.y = JUMP_SPEED;
+                        enemy.velocity.x = -away * PLAYER_SPEED;
+                    } else if enemy.wall == -away && enemy.velocity.y <= 0. {
+                        wall_jump(&mut enemy);
+                        e.motion = if away < 0. { Motion::Left } else { Motion::Right };
+                    } else {
+                        enemy.velocity.x = -away * PLAYER_SPEED;
+                        enemy.velocity.y += GRAVITY;
+                        wall_slide(&mut enemy);
+                    }
+                }
+                Motion::Fall => {
+                    //falling while standing on a one way platform means dropping through it
+                    if enemy.on_one_way {
+                        enemy.drop_frames = DROP_FRAMES;
+                    }
+                    enemy.velocity.x = 0.;
+                    enemy.velocity.y += GRAVITY;
+                }
+                Motion::Stop | Motion::Ride => {
+                    enemy.velocity.x = 0.;
+                    enemy.velocity.y += GRAVITY;
+                }
+            }
+            enemy.velocity.x *= effects.speed_scale();
+            //on slippery surfaces enemies only get part of the way to the speed they want
+            enemy.velocity.x = old_velocity + (enemy.velocity.x - old_velocity) * enemy.friction;
+        }
+        change.y = enemy.velocity.y;
+        change.x = enemy.velocity.x;
+        //}  //comment out when enemy should move freely
+        //this holds the position the player will end up in if there is no collision
+        enemy.projected_position = et.translation + Vec3::new(change.x, change.y, 0.);
+        enemy.grounded = false;
+        enemy.on_one_way = false;
+        if enemy.drop_frames > 0 {
+            enemy.drop_frames -= 1;
+        }
+    }
+}
+
+pub fn attack_enemies(
+    enemies: Query<(&Health, &Transform, &Enemy, &StatusEffects), With<Enemy>>,
+    mut commands: Commands,
+    count: Res<TickCount>,
+    difficulty: Res<Difficulty>,
+) {
+    if count.ticks % difficulty.preset().attack_ticks != 0 {
+        return;
+    }
+    for (health, et, e, effects) in enemies.iter() {
+        if effects.stunned() {
+            continue;
+        }
+        let hitbox: Vec3;
+
+        match &e.attack {
+            Attack::Up => match &e.t {
+                Type::Melee => {
+                    hitbox = Vec3::new(et.translation.x, et.translation.y + PLAYER_SZ, 0.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: hitbox,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(MeleeBox::new(hitbox));
+                }
+                Type::Ranged => {}
+                Type::Hybrid => {
+                    hitbox = Vec3::new(et.translation.x, et.translation.y + PLAYER_SZ, 0.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: hitbox,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(MeleeBox::new(hitbox));
+                }
+            },
+            Attack::Down => match &e.t {
+                Type::Melee => {
+                    hitbox = Vec3::new(et.translation.x, et.translation.y - PLAYER_SZ, 0.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: hitbox,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(MeleeBox::new(hitbox));
+                }
+                Type::Ranged => {}
+                Type::Hybrid => {
+                    hitbox = Vec3::new(et.translation.x, et.translation.y - PLAYER_SZ, 0.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: hitbox,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(MeleeBox::new(hitbox));
+                }
+            },
+            Attack::Left => match &e.t {
+                Type::Melee => {
+                    hitbox = Vec3::new(et.translation.x - PLAYER_SZ, et.translation.y, 0.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: hitbox,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(MeleeBox::new(hitbox));
+                }
+                Type::Ranged => {
+                    let vel = Vec2::new(-15., 4.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: Vec3::new(
+                                    et.translation.x - PLAYER_SZ,
+                                    et.translation.y,
+                                    2.,
+                                ),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(Projectile::new(vel, ProjType::EnemyProjectile));
+                }
+                Type::Hybrid => {
+                    if health.current > e.retreat_health {
+                        hitbox = Vec3::new(et.translation.x - PLAYER_SZ, et.translation.y, 0.);
+                        commands
+                            .spawn_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    color: Color::GREEN,
+                                    custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                    ..default()
+                                },
+                                transform: Transform {
+                                    translation: hitbox,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .insert(MeleeBox::new(hitbox));
+                    } else {
+                        let vel = Vec2::new(-15., 4.);
+                        commands
+                            .spawn_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    color: Color::GREEN,
+                                    custom_size: Some(Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ)),
+                                    ..default()
+                                },
+                                transform: Transform {
+                                    translation: Vec3::new(
+                                        et.translation.x - PLAYER_SZ,
+                                        et.translation.y,
+                                        2.,
+                                    ),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .insert(Projectile::new(vel, ProjType::EnemyProjectile));
+                    }
+                }
+            },
+            Attack::Right => match &e.t {
+                Type::Melee => {
+                    hitbox = Vec3::new(et.translation.x + PLAYER_SZ, et.translation.y, 0.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: hitbox,
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(MeleeBox::new(hitbox));
+                }
+                Type::Ranged => {
+                    let vel = Vec2::new(15., 4.);
+                    commands
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::GREEN,
+                                custom_size: Some(Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ)),
+                                ..default()
+                            },
+                            transform: Transform {
+                                translation: Vec3::new(
+                                    et.translation.x + PLAYER_SZ,
+                                    et.translation.y,
+                                    2.,
+                                ),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(Projectile::new(vel, ProjType::EnemyProjectile));
+                }
+                Type::Hybrid => {
+                    if health.current > e.retreat_health {
+                        hitbox = Vec3::new(et.translation.x + PLAYER_SZ, et.translation.y, 0.);
+                        commands
+                            .spawn_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    color: Color::GREEN,
+                                    custom_size: Some(Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.)),
+                                    ..default()
+                                },
+                                transform: Transform {
+                                    translation: hitbox,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .insert(MeleeBox::new(hitbox));
+                    } else {
+                        let vel = Vec2::new(15., 4.);
+                        commands
+                            .spawn_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    color: Color::GREEN,
+                                    custom_size: Some(Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ)),
+                                    ..default()
+                                },
+                                transform: Transform {
+                                    translation: Vec3::new(
+                                        et.translation.x + PLAYER_SZ,
+                                        et.translation.y,
+                                        2.,
+                                    ),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .insert(Projectile::new(vel, ProjType::EnemyProjectile));
+                    }
+                }
+            },
+            Attack::None => {}
+        }
+    }
+}
+
+pub fn meleebox_collisions(
+    melee_box: Query<(&MeleeBox, Entity), (With<MeleeBox>, Without<Player>)>,
+    mut commands: Commands,
+    player: Query<(&ActiveObject, &Player, Entity), With<Player>>,
+    mut objects: Query<
+        (&mut Object, &Transform, Entity),
+        (With<Object>, Without<Player>, Without<Projectile>),
+    >,
+    difficulty: Res<Difficulty>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    for (obj, entity) in melee_box.iter() {
+        for (pl, p, pe) in player.iter() {
+            let res = bevy::sprite::collide_aabb::collide(
+                obj.position,
+                Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.),
+                pl.projected_position,
+                Vec2::new(PLAYER_SZ, PLAYER_SZ),
+            );
+            //dashing players are out of reach for a moment
+            if res.is_some() && !p.dash.invulnerable() {
+                commands.entity(entity).despawn();
+                damage.send(DamageEvent {
+                    target: pe,
+                    amount: difficulty.preset().melee_damage as i32,
+                    source: Some(entity),
+                    kind: DamageKind::Melee,
+                });
+            }
+        }
+        for (mut object, object_t, object_entity) in objects.iter_mut() {
+            let res = bevy::sprite::collide_aabb::collide(
+                obj.position,
+                Vec2::new(PLAYER_SZ * 2., PLAYER_SZ * 2.),
+                object_t.translation,
+                Vec2::new(object.width, object.height),
+            );
+            if res.is_some() {
+                if matches!(object.obj_type, ObjectType::Breakable)
+                    || matches!(object.obj_type, ObjectType::Breakable)
+                {
+                    object.broken = true;
+                }
+            }
+        }
+    }
+}
+
+//enemies burst apart when they die
+pub fn kill_enemies(
+    mut deaths: EventReader<DeathEvent>,
+    enemies: Query<&ActiveObject, With<Enemy>>,
+    mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
+) {
+    for death in deaths.iter() {
+        let a = match enemies.get(death.entity) {
+            Ok(a) => a,
+            Err(_) => continue,
+        };
+        let rng = &mut game_rng.rng;
+        for i in 1..6 {
+            let sz = 48. / rng.gen_range(8, 16) as f32;
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::RED,
+                        custom_size: Some(Vec2::new(sz, sz)),
+                        ..default()
+                    },
+                    transform: Transform {
+                        translation: a.projected_position,
+                        ..default()
+                    },
+                    // texture: asset_server.load("bullet.png"),
+                    ..default()
+                })
+                .insert(Projectile::new(
+                    Vec2::new(rng.gen_range(-5, 5) as f32, rng.gen_range(2, 7) as f32),
+                    ProjType::BrokenObj,
+                ))
+                .insert(BrokenObj::new(Timer::from_seconds(4.0, false)));
+        }
+        commands.entity(death.entity).despawn();
+    }
+}
+
+//ammo for every enemy killed, and a credit dropped where it died
+pub fn reward_kills(
+    mut commands: Commands,
+    mut deaths: EventReader<DeathEvent>,
+    enemies: Query<&Transform, With<Enemy>>,
+    mut player: Query<&mut Player>,
+    mut stats: ResMut<RunStats>,
+) {
+    let mut p = player.single_mut();
+    for death in deaths.iter() {
+        if let Ok(et) = enemies.get(death.entity) {
+            spawn_credit(&mut commands, et.translation.truncate());
+            p.arsenal.add(KILL_AMMO);
+            stats.kills += 1;
+        }
+    }
+}
+
+//touching a dropped credit picks it up
+pub fn collect_credits(
+    mut commands: Commands,
+    mut player: Query<(&Transform, &Object, &mut Player)>,
+    credits: Query<(Entity, &Transform, &Object), Without<Player>>,
+    mut stats: ResMut<RunStats>,
+) {
+    let (pt, po, mut p) = player.single_mut();
+    for (e, t, o) in credits.iter() {
+        if !matches!(o.obj_type, ObjectType::Credit) {
+            continue;
+        }
+        if bevy::sprite::collide_aabb::collide(pt.translation, Vec2::new(po.width, po.height), t.translation, Vec2::new(o.width, o.height)).is_some() {
+            commands.entity(e).despawn();
+            p.credits += KILL_CREDITS;
+            stats.credits_earned += KILL_CREDITS;
+        }
+    }
+}
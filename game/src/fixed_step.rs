@@ -0,0 +1,184 @@
+use crate::game_state::*;
+use crate::replay::*;
+use bevy::ecs::schedule::IntoSystemDescriptor;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use iyes_loopless::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+//name of the fixed timestep every gameplay system runs in
+pub const FIXED_STEP: &str = "my_fixed_update";
+//length of one simulation tick, anything that counts time in gameplay should use this instead of Time
+pub const TICK: Duration = Duration::from_millis(17);
+//enemies used to attack on their own 250ms timestep, now they attack every this many ticks
+pub const ENEMY_ATTACK_TICKS: u64 = 15;
+
+//the stages of one tick, in the order they run
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Step {
+    Input,
+    Movement,
+    Platforms,
+    Collisions,
+    Positions,
+    Combat,
+    Sight,
+    Level,
+}
+
+//every gameplay system, so systems in the same step can be ordered against each other
+//run_in_state wraps a system in a way that loses its function label, so these are used instead
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameplaySystem {
+    MovePlayer,
+    SwingOnRope,
+    MoveEnemies,
+    GravityOnMovables,
+    Attack,
+    Shoot,
+    ItemShop,
+    AttackEnemies,
+    MovePlatforms,
+    ChangePlayerDirection,
+    BarrelsWithBarrels,
+    ApplyCollisions,
+    EnemyCollisions,
+    ObjectCollisions,
+    UpdatePositions,
+    AttackStatic,
+    AttackActive,
+    MeleeboxCollisions,
+    ProjectileActiveCollision,
+    ProjectileStaticCollisions,
+    TickStatusEffects,
+    ApplyDamage,
+    KillEnemies,
+    RewardKills,
+    BreakObjects,
+    BreakHbObjects,
+    DespawnBrokenObjects,
+    PlayerHealth,
+    CalculateSight,
+    CollectAbilityPickups,
+    CollectTimePickups,
+    CollectCredits,
+    TickClock,
+    CreateLevel,
+    ChecksumWorld,
+    TrackRun,
+}
+
+//insert before adding the plugins to step gameplay by hand with app.update() instead of on a timer
+pub struct ManualStepping;
+
+pub trait AddGameplaySystem {
+    fn add_gameplay_system<Params>(&mut self, system: impl IntoSystemDescriptor<Params>) -> &mut Self;
+}
+
+impl AddGameplaySystem for App {
+    fn add_gameplay_system<Params>(&mut self, system: impl IntoSystemDescriptor<Params>) -> &mut Self {
+        if self.world.contains_resource::<ManualStepping>() {
+            self.add_system(system)
+        } else {
+            self.add_fixed_timestep_system(FIXED_STEP, 0, system)
+        }
+    }
+}
+
+//sets up the tick every other gameplay plugin adds its systems to, add it first
+pub struct FixedStepPlugin;
+
+impl Plugin for FixedStepPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<ManualStepping>() {
+            app.add_fixed_timestep(TICK, FIXED_STEP);
+        }
+        //so a run can be repeated with GAME_SEED, logged here since logging only starts with the default plugins
+        if let Some(rng) = app.world.get_resource::<GameRng>() {
+            info!("Game seed: {}", rng.seed);
+        }
+        app.insert_resource(TickInput::new())
+            .insert_resource(TickCount::new())
+            //every tick runs the same systems in the same order, so the same inputs and seed give the same game
+            .add_gameplay_system(read_input.run_in_state(GameState::Playing).label(Step::Input))
+            .add_gameplay_system(checksum_world.run_in_state(GameState::Playing).label(GameplaySystem::ChecksumWorld).after(Step::Level))
+            .add_system_to_stage(CoreStage::Last, save_recording);
+    }
+}
+
+//counts ticks since the game started
+pub struct TickCount {
+    pub ticks: u64,
+}
+impl TickCount {
+    pub fn new() -> Self {
+        Self { ticks: 0 }
+    }
+}
+
+//keyboard state latched once per tick, so a key press is seen by exactly one tick
+//no matter how many ticks run in a frame
+pub struct TickInput {
+    pressed: HashSet<KeyCode>,
+    previous: HashSet<KeyCode>,
+}
+impl TickInput {
+    pub fn new() -> Self {
+        Self {
+            pressed: HashSet::default(),
+            previous: HashSet::default(),
+        }
+    }
+    pub fn pressed(&self, key: KeyCode) -> bool {
+        self.pressed.contains(&key)
+    }
+    pub fn just_pressed(&self, key: KeyCode) -> bool {
+        self.pressed.contains(&key) && !self.previous.contains(&key)
+    }
+    //move to the next tick with these keys held
+    pub fn set(&mut self, keys: impl Iterator<Item = KeyCode>) {
+        self.previous = std::mem::take(&mut self.pressed);
+        self.pressed = keys.collect();
+    }
+}
+
+//uses GAME_SEED if it is set, otherwise picks one from the clock
+pub fn seed_from_env() -> u64 {
+    match std::env::var("GAME_SEED").ok().and_then(|s| s.parse().ok()) {
+        Some(seed) => seed,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0),
+    }
+}
+
+//all gameplay randomness comes from here so a run can be repeated from its seed
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed: seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+//first system of every tick
+pub fn read_input(
+    keys: Res<Input<KeyCode>>,
+    mut input: ResMut<TickInput>,
+    mut count: ResMut<TickCount>,
+    mut replay: ResMut<Replay>,
+) {
+    //a key tapped and released inside one frame still counts for this tick
+    let live = key_mask(keys.get_pressed().chain(keys.get_just_pressed()).copied());
+    //while a replay is playing the keyboard is ignored
+    input.set(mask_keys(replay.next_frame(live)));
+    count.ticks += 1;
+}
@@ -0,0 +1,151 @@
+use crate::difficulty::*;
+use crate::fixed_step::*;
+use crate::replay::*;
+use crate::util::*;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+//visiting every room from 1 up to this one wins the run
+pub const LAST_ROOM: i8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    MainMenu,
+    Playing,
+    Paused,
+    GameOver,
+    Victory,
+    //changing rooms, the game is frozen until the new room is in
+    Transition,
+}
+
+//what happened during the current run, shown when it ends
+pub struct RunStats {
+    pub ticks: u64,
+    pub kills: u32,
+    pub credits_earned: i32,
+    pub rooms_visited: Vec<i8>,
+    pub cause: String,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        Self {
+            ticks: 0,
+            kills: 0,
+            credits_earned: 0,
+            rooms_visited: Vec::new(),
+            cause: String::new(),
+        }
+    }
+    pub fn seconds(&self) -> f32 {
+        self.ticks as f32 * TICK.as_secs_f32()
+    }
+}
+
+//progress that carries over between runs and gets saved
+pub struct Progress {
+    pub discovered: Vec<i8>,
+    pub unlocks: Vec<String>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self {
+            discovered: Vec::new(),
+            unlocks: Vec::new(),
+        }
+    }
+    pub fn discover(&mut self, room: i8) {
+        if !self.discovered.contains(&room) {
+            self.discovered.push(room);
+        }
+    }
+    pub fn unlocked(&self, name: &str) -> bool {
+        self.unlocks.iter().any(|u| u == name)
+    }
+    pub fn unlock(&mut self, name: &str) {
+        if !self.unlocked(name) {
+            self.unlocks.push(name.to_string());
+        }
+    }
+}
+
+//ends the run, the game over screen takes it from there
+pub fn game_over(commands: &mut Commands, stats: &mut RunStats, cause: &str) {
+    stats.cause = cause.to_string();
+    commands.insert_resource(NextState(GameState::GameOver));
+}
+
+//gameplay systems only run while Playing, so every other state freezes the game
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        //tests and replays skip the menu and go straight into the game
+        let replaying = match app.world.get_resource::<Replay>() {
+            Some(replay) => matches!(replay.mode, ReplayMode::Replay),
+            None => false,
+        };
+        let start = if replaying || app.world.contains_resource::<ManualStepping>() {
+            GameState::Playing
+        } else {
+            GameState::MainMenu
+        };
+        if !app.world.contains_resource::<Difficulty>() {
+            app.insert_resource(Difficulty::Normal);
+        }
+        //the save plugin or a test may already have loaded progress
+        if !app.world.contains_resource::<Progress>() {
+            app.insert_resource(Progress::new());
+        }
+        app.add_loopless_state(start)
+            .insert_resource(RunStats::new())
+            .add_gameplay_system(track_run.run_in_state(GameState::Playing).label(GameplaySystem::TrackRun).after(GameplaySystem::ChecksumWorld))
+            .add_system(toggle_pause)
+            .add_system(skip_menus_in_replay);
+    }
+}
+
+pub fn track_run(
+    mut commands: Commands,
+    mut stats: ResMut<RunStats>,
+    mut progress: ResMut<Progress>,
+    manager: Query<&Manager>,
+) {
+    stats.ticks += 1;
+    let m = manager.single();
+    progress.discover(m.room_number);
+    if m.room_number > 0 && !stats.rooms_visited.contains(&m.room_number) {
+        stats.rooms_visited.push(m.room_number);
+        if (1..=LAST_ROOM).all(|room| stats.rooms_visited.contains(&room)) {
+            stats.cause = "Visited every room".to_string();
+            commands.insert_resource(NextState(GameState::Victory));
+        }
+    }
+}
+
+//Esc pauses and unpauses, this reads the keyboard directly since gameplay input stops while paused
+pub fn toggle_pause(mut commands: Commands, keys: Res<Input<KeyCode>>, state: Res<CurrentState<GameState>>) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    match state.0 {
+        GameState::Playing => commands.insert_resource(NextState(GameState::Paused)),
+        GameState::Paused => commands.insert_resource(NextState(GameState::Playing)),
+        _ => {}
+    }
+}
+
+//nobody is there to press Enter during a replay, so restart on our own
+pub fn skip_menus_in_replay(
+    mut commands: Commands,
+    replay: Res<Replay>,
+    state: Res<CurrentState<GameState>>,
+) {
+    if matches!(replay.mode, ReplayMode::Replay)
+        && matches!(state.0, GameState::GameOver | GameState::Victory)
+    {
+        commands.insert_resource(NextState(GameState::Playing));
+    }
+}
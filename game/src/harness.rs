@@ -0,0 +1,137 @@
+//runs the game without a window so gameplay can be tested, see tests/gameplay.rs
+//every update of the app is exactly one fixed tick
+use crate::active_util::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::level::*;
+use crate::replay::*;
+use crate::util::*;
+use crate::*;
+use bevy::asset::AssetPlugin;
+use bevy::prelude::*;
+
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    //loads room `room` through create_level and runs its first tick
+    pub fn new(room: i8) -> Self {
+        Self::with_progress(room, Progress::new())
+    }
+
+    //like `new`, but as if `progress` had been loaded from the save file
+    pub fn with_progress(room: i8, progress: Progress) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>()
+            .add_asset::<Mesh>()
+            .add_asset::<ColorMaterial>()
+            .add_asset::<TextureAtlas>()
+            .insert_resource(GameRng::new(0))
+            .insert_resource(Replay::new(0, room))
+            .insert_resource(Input::<KeyCode>::default())
+            .insert_resource(ManualStepping)
+            .insert_resource(progress)
+            //rooms swap as soon as they are built so tests see the new room on the next tick
+            .insert_resource(RoomTransition::new(TransitionStyle::Fade, 0., false))
+            //everything but the HUD, which needs a window
+            .add_plugin(FixedStepPlugin)
+            .add_plugin(GameStatePlugin)
+            .add_plugin(LevelPlugin)
+            .add_plugin(TimerPlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(AiPlugin)
+            .add_plugin(PhysicsPlugin)
+            .add_plugin(ShopPlugin)
+            .add_plugin(StatusPlugin)
+            .add_plugin(HealthPlugin);
+        let mut harness = Self { app: app };
+        harness.step(1, &[]);
+        harness
+    }
+
+    //runs `ticks` ticks with exactly `keys` held down
+    pub fn step(&mut self, ticks: u32, keys: &[KeyCode]) {
+        let mut input = self.app.world.resource_mut::<Input<KeyCode>>();
+        let held: Vec<KeyCode> = input.get_pressed().copied().collect();
+        for key in held {
+            if !keys.contains(&key) {
+                input.release(key);
+            }
+        }
+        for key in keys {
+            input.press(*key);
+        }
+        for _ in 0..ticks {
+            self.app.update();
+            self.app.world.resource_mut::<Input<KeyCode>>().clear();
+            //a room change takes extra updates that are not gameplay ticks
+            while self.app.world.resource::<RoomTransition>().active() {
+                self.app.update();
+            }
+        }
+    }
+
+    //presses `key` for one tick, then lets go of everything for `ticks` more
+    pub fn tap(&mut self, key: KeyCode, ticks: u32) {
+        self.step(1, &[key]);
+        self.step(ticks, &[]);
+    }
+
+    pub fn player(&mut self) -> &ActiveObject {
+        let mut query = self.app.world.query_filtered::<&ActiveObject, With<Player>>();
+        query.single(&self.app.world)
+    }
+
+    pub fn player_data(&mut self) -> &Player {
+        let mut query = self.app.world.query::<&Player>();
+        query.single(&self.app.world)
+    }
+
+    pub fn player_position(&mut self) -> Vec3 {
+        let mut query = self.app.world.query_filtered::<&Transform, With<Player>>();
+        query.single(&self.app.world).translation
+    }
+
+    //moves the player to `x`, `y` in pixels, standing still
+    pub fn place_player(&mut self, x: f32, y: f32) {
+        let mut query = self.app.world.query_filtered::<(&mut ActiveObject, &mut Transform), With<Player>>();
+        let (mut pl, mut pt) = query.single_mut(&mut self.app.world);
+        pt.translation.x = x;
+        pt.translation.y = y;
+        pl.projected_position = pt.translation;
+        pl.velocity = Vec2::ZERO;
+    }
+
+    //goes to the start of room `room` as if through a teleporter
+    pub fn go_to_room(&mut self, room: i8) {
+        let mut query = self.app.world.query::<&mut Manager>();
+        let mut m = query.single_mut(&mut self.app.world);
+        m.room_number = room;
+        m.spawn = "start";
+        self.step(1, &[]);
+    }
+
+    pub fn room(&mut self) -> i8 {
+        let mut query = self.app.world.query::<&Manager>();
+        query.single(&self.app.world).room_number
+    }
+
+    //how many level objects match `f`
+    pub fn count(&mut self, f: impl Fn(&Object) -> bool) -> usize {
+        let mut query = self.app.world.query::<&Object>();
+        query.iter(&self.app.world).filter(|o| f(o)).count()
+    }
+
+    //puts a static object into the room, sizes and positions are in pixels
+    pub fn spawn_object(&mut self, obj_type: ObjectType, x: f32, y: f32, width: f32, height: f32) {
+        self.app
+            .world
+            .spawn()
+            .insert(Object::new(-2, width, height, obj_type))
+            .insert(Transform::from_xyz(x, y, 2.))
+            .insert(GlobalTransform::default());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::status_effects::*;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+pub const PLAYER_HEALTH: i32 = 100;
+
+//what dealt the damage, for resistances and for what a death gets blamed on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageKind {
+    Melee,
+    Projectile,
+    Explosion,
+    //falling pieces of broken objects
+    Debris,
+    //burns, poison and bleeding, see status_effects.rs
+    Status,
+}
+
+pub const DAMAGE_KINDS: usize = 5;
+
+#[derive(Component)]
+pub struct Health {
+    pub current: i32,
+    pub max: i32,
+    //taken off every hit after resistances
+    pub armor: i32,
+    //times the damage taken of each DamageKind, 1 is normal
+    pub resistances: [f32; DAMAGE_KINDS],
+}
+
+impl Health {
+    pub fn new(max: i32) -> Self {
+        Self {
+            current: max,
+            max: max,
+            armor: 0,
+            resistances: [1.; DAMAGE_KINDS],
+        }
+    }
+    pub fn alive(&self) -> bool {
+        self.current > 0
+    }
+    pub fn fraction(&self) -> f32 {
+        (self.current as f32 / self.max as f32).clamp(0., 1.)
+    }
+    pub fn heal(&mut self, amount: i32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+    //how much of a hit gets through armor and resistances
+    pub fn absorb(&self, amount: i32, kind: DamageKind) -> i32 {
+        ((amount as f32 * self.resistances[kind as usize]).round() as i32 - self.armor).max(0)
+    }
+}
+
+//everything that hurts the player or an enemy sends one of these instead of changing its Health
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: i32,
+    //the hitbox, projectile or melee box that did it, None for spikes and status effects
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+}
+
+//sent once when something's health runs out, with the hit that did it
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+}
+
+//turns damage into lost health and deaths
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_gameplay_system(apply_damage.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::ApplyDamage).after(GameplaySystem::TickStatusEffects).after(Step::Movement));
+    }
+}
+
+pub fn apply_damage(
+    mut damage: EventReader<DamageEvent>,
+    mut deaths: EventWriter<DeathEvent>,
+    mut targets: Query<(&mut Health, Option<&StatusEffects>)>,
+) {
+    for hit in damage.iter() {
+        let (mut health, effects) = match targets.get_mut(hit.target) {
+            Ok(target) => target,
+            Err(_) => continue,
+        };
+        if !health.alive() {
+            continue;
+        }
+        //effects like poison make every other hit hurt more
+        let amount = match (effects, hit.kind) {
+            (Some(effects), kind) if kind != DamageKind::Status => effects.hurt(hit.amount),
+            _ => hit.amount,
+        };
+        health.current -= health.absorb(amount, hit.kind);
+        if !health.alive() {
+            deaths.send(DeathEvent {
+                entity: hit.target,
+                source: hit.source,
+                kind: hit.kind,
+            });
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use crate::active_util::*;
+use crate::difficulty::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::items::jetpack::*;
+use crate::level::*;
+use crate::save::*;
+use crate::status_effects::*;
+use crate::timer::*;
+use crate::util::*;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use iyes_loopless::prelude::*;
+
+#[derive(Component, Deref, DerefMut)]
+struct PopupTimer(Timer);
+
+//text for whichever menu screen is up, cleared when its state is left
+#[derive(Component)]
+struct MenuText;
+
+//full screen panel that hides the swap when changing rooms
+#[derive(Component)]
+struct TransitionCover;
+
+//jetpack fuel bar next to the health, and the part of it that fills up
+#[derive(Component)]
+struct FuelGauge;
+#[derive(Component)]
+struct FuelLevel;
+
+//the weapon that's out and its ammo
+#[derive(Component)]
+struct WeaponText;
+
+//one square per status effect, shown while the player has it
+#[derive(Component)]
+struct StatusIcon(Effect);
+
+const STATUS_ICON_SZ: f32 = 20.;
+
+//camera, clock, credits and health text, only needed when there is a window
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_hud)
+            .add_system(follow_player)
+            .add_system(my_cursor_system)
+            .add_system(show_gui)
+            .add_system(menu_input)
+            .add_system(draw_transition)
+            .add_system(warn_clock)
+            .add_system(show_fuel)
+            .add_system(show_weapon)
+            .add_system(show_status_icons)
+            .add_enter_system(GameState::MainMenu, show_main_menu)
+            .add_enter_system(GameState::Paused, show_pause)
+            .add_enter_system(GameState::GameOver, show_run_end)
+            .add_enter_system(GameState::Victory, show_run_end)
+            .add_exit_system(GameState::MainMenu, clear_menu)
+            .add_exit_system(GameState::Paused, clear_menu)
+            .add_exit_system(GameState::GameOver, clear_menu)
+            .add_exit_system(GameState::Victory, clear_menu);
+    }
+}
+
+pub fn setup_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    // let assets = HashMap::new();
+    // let sprites = &["barrel.png","boots.png","breakable.png","jetpack.png","spikes.png","umbrella.png"];
+    // for sprite in sprites {
+    //     assets.insert(sprite, asset_server.load(*sprite));
+    // }
+    // while asset_server.get_group_load_state(assets.values().into_iter()) != LoadState::Loaded{}
+
+    let images = &[
+        "jacob.png",
+        "bailey.png",
+        "brian.png",
+        "ethan.png",
+        "jack.png",
+        "gio.png",
+        "zach.png",
+    ];
+
+    commands.spawn_bundle(Camera2dBundle::default());
+    let mut time: f32 = 0.0;
+    for image in images {
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: asset_server.load(*image),
+                transform: Transform::from_xyz(0., 0., -1.),
+                ..default()
+            })
+            .insert(PopupTimer(Timer::from_seconds(time, false)));
+        time += 5.0;
+    }
+
+    commands
+        .spawn_bundle(TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 100.0,
+                color: Color::WHITE,
+                font: asset_server.load("mrsmonster.ttf"),
+            },
+        ))
+        .insert(Style {
+            align_self: AlignSelf::FlexEnd,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(5.0),
+                right: Val::Px(15.0),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(ClockText);
+
+    //spawn creditText
+    commands
+        .spawn_bundle(TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 100.0,
+                color: Color::YELLOW,
+                font: asset_server.load("mrsmonster.ttf"),
+            },
+        ))
+        .insert(Style {
+            align_self: AlignSelf::FlexEnd,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(5.0),
+                left: Val::Px(15.0),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(CreditText);
+
+    //spawn healthbar
+    commands
+        .spawn_bundle(TextBundle::from_section(
+            "100",
+            TextStyle {
+                font_size: 100.0,
+                color: Color::RED,
+                font: asset_server.load("mrsmonster.ttf"),
+            },
+        ))
+        .insert(Style {
+            align_self: AlignSelf::FlexEnd,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(15.0),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(HealthBar);
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(200.0),
+                    top: Val::Px(45.0),
+                    ..default()
+                },
+                size: Size::new(Val::Px(150.0), Val::Px(20.0)),
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(FuelGauge)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        ..default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..default()
+                })
+                .insert(FuelLevel);
+        });
+
+    commands
+        .spawn_bundle(TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 40.0,
+                color: Color::WHITE,
+                font: asset_server.load("mrsmonster.ttf"),
+            },
+        ))
+        .insert(Style {
+            align_self: AlignSelf::FlexEnd,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(200.0),
+                top: Val::Px(75.0),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(WeaponText);
+
+    for effect in EFFECTS {
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(200.0),
+                        top: Val::Px(120.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(STATUS_ICON_SZ), Val::Px(STATUS_ICON_SZ)),
+                    ..default()
+                },
+                color: UiColor(Color::NONE),
+                ..default()
+            })
+            .insert(StatusIcon(effect));
+    }
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Px(WIN_W), Val::Px(WIN_H)),
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(TransitionCover);
+}
+
+//fades the cover in and out, or slides it across from the side the player left through and off the other side
+fn draw_transition(transition: Res<RoomTransition>, mut cover: Query<(&mut UiColor, &mut Style), With<TransitionCover>>) {
+    let (mut color, mut style) = cover.single_mut();
+    match transition.style {
+        TransitionStyle::Fade => {
+            color.0 = Color::rgba(0., 0., 0., transition.cover);
+            style.position.left = Val::Px(0.);
+        }
+        TransitionStyle::Slide => {
+            let side = if transition.covering { transition.direction } else { -transition.direction };
+            color.0 = Color::BLACK;
+            style.position.left = Val::Px(side * (1. - transition.cover) * WIN_W);
+        }
+    }
+}
+
+//we can probably add this as an event, to be used when the level id is outside of the possible range
+fn show_popup(time: Res<Time>, mut popup: Query<(&mut PopupTimer, &mut Transform)>) {
+    let mut count = 1.0;
+    for (mut timer, mut transform) in popup.iter_mut() {
+        timer.tick(time.delta());
+        if timer.just_finished() {
+            transform.translation.z = count;
+        }
+        count += 1.0;
+    }
+}
+
+//the camera follows the player, the simulation never moves it
+pub fn follow_player(
+    player: Query<&Transform, With<Player>>,
+    mut cam: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+) {
+    let pt = player.single();
+    let mut camera = cam.single_mut();
+    if pt.translation.x + WIN_W / 2. < MAP_W / 2. && pt.translation.x - WIN_W / 2. > -MAP_W / 2. {
+        camera.translation.x = pt.translation.x;
+    } else if pt.translation.x > 0. {
+        camera.translation.x = MAP_W / 2. - WIN_W / 2.;
+    } else {
+        camera.translation.x = -MAP_W / 2. + WIN_W / 2.;
+    }
+    if pt.translation.y + WIN_H / 2. < MAP_H / 2. && pt.translation.y - WIN_H / 2. > -MAP_H / 2. {
+        camera.translation.y = pt.translation.y;
+    } else if pt.translation.y > 0. {
+        camera.translation.y = MAP_H / 2. - WIN_H / 2.;
+    } else {
+        camera.translation.y = -MAP_H / 2. + WIN_H / 2.;
+    }
+    camera.translation.y = pt.translation.y;
+}
+
+//used for debugging and finding tile coordinates, nothing else. Player start tile is considered (0,0) for sanity.
+pub fn my_cursor_system(
+    mouse_input: Res<Input<MouseButton>>,
+    // need to get window dimensions
+    wnds: Res<Windows>,
+    // query to get camera transform
+    q_camera: Query<(&Camera, &GlobalTransform), With<Camera>>,
+) {
+    // get the camera info and transform
+    // assuming there is exactly one main camera entity, so query::single() is OK
+    let (camera, camera_transform) = q_camera.single();
+
+    // get the window that the camera is displaying to (or the primary window)
+    let wnd = if let RenderTarget::Window(id) = camera.target {
+        wnds.get(id).unwrap()
+    } else {
+        wnds.get_primary().unwrap()
+    };
+
+    // check if the cursor is inside the window and get its position
+    if let Some(screen_pos) = wnd.cursor_position() {
+        // get the size of the window
+        let window_size = Vec2::new(wnd.width() as f32, wnd.height() as f32);
+
+        // convert screen position [0..resolution] to ndc [-1..1] (gpu coordinates)
+        let ndc = (screen_pos / window_size) * 2.0 - Vec2::ONE;
+
+        // matrix for undoing the projection and camera transform
+        let ndc_to_world = camera_transform.compute_matrix() * camera.projection_matrix().inverse();
+
+        // use it to convert ndc to world-space coordinates
+        let world_pos = ndc_to_world.project_point3(ndc.extend(-1.0));
+
+        // reduce it to a 2D value
+        let world_pos: Vec2 = world_pos.truncate();
+        if mouse_input.just_pressed(MouseButton::Left) {
+            eprintln!(
+                "World coords: {}/{}",
+                (world_pos.x / 32.).round(),
+                (world_pos.y / 32.).round()
+            );
+        }
+    }
+}
+
+pub fn show_gui(
+    player: Query<(&Player, &Health), With<Player>>,
+    clock: Res<Clock>,
+    mut text: Query<&mut Text, (With<ClockText>, Without<CreditText>, Without<HealthBar>)>,
+    mut credit_text: Query<&mut Text, (With<CreditText>, Without<ClockText>, Without<HealthBar>)>,
+    mut healthbar: Query<&mut Text, (With<HealthBar>, Without<ClockText>, Without<CreditText>)>,
+) {
+    let (p, health) = player.single();
+    let time_remaining = clock.remaining().round();
+    //println!("{}", time_remaining);
+    for mut text in &mut text {
+        if time_remaining > 0.0 {
+            text.sections[0].value = time_remaining.to_string();
+        }
+    }
+
+    for mut text in &mut credit_text {
+        text.sections[0].value = p.credits.to_string();
+    }
+
+    for mut text in &mut healthbar {
+        text.sections[0].value = health.current.to_string();
+    }
+}
+
+//only shown while the jetpack is on, flashing while it sputters and red once it overheats
+fn show_fuel(
+    time: Res<Time>,
+    player: Query<&Player>,
+    mut gauge: Query<&mut UiColor, (With<FuelGauge>, Without<FuelLevel>)>,
+    mut level: Query<(&mut UiColor, &mut Style), With<FuelLevel>>,
+) {
+    let p = player.single();
+    let shown = p.equipped().name() == JETPACK;
+    for mut color in &mut gauge {
+        *color = UiColor(if shown { Color::rgba(0., 0., 0., 0.6) } else { Color::NONE });
+    }
+    let flash = (time.seconds_since_startup() * 8.) as i64 % 2 == 0;
+    for (mut color, mut style) in &mut level {
+        style.size.width = Val::Percent(p.fuel.fraction() * 100.);
+        *color = UiColor(if !shown {
+            Color::NONE
+        } else if p.fuel.overheated {
+            Color::RED
+        } else if p.fuel.sputtering() && flash {
+            Color::YELLOW
+        } else {
+            Color::ORANGE
+        });
+    }
+}
+
+//the effects the player has line up left to right under the weapon
+fn show_status_icons(player: Query<&StatusEffects, With<Player>>, mut icons: Query<(&StatusIcon, &mut UiColor, &mut Style)>) {
+    let effects = player.single();
+    for (icon, mut color, mut style) in &mut icons {
+        let slot = EFFECTS.iter().filter(|e| effects.has(**e)).position(|e| *e == icon.0);
+        *color = UiColor(if slot.is_some() { icon.0.info().color } else { Color::NONE });
+        style.position.left = Val::Px(200. + slot.unwrap_or(0) as f32 * (STATUS_ICON_SZ + 6.));
+    }
+}
+
+fn show_weapon(player: Query<&Player>, mut text: Query<&mut Text, With<WeaponText>>) {
+    let arsenal = &player.single().arsenal;
+    for mut text in &mut text {
+        text.sections[0].value = format!("{}: {}", arsenal.current().name, arsenal.ammo_left());
+    }
+}
+
+//the clock turns red once time is running low, until more time is added or it runs out
+fn warn_clock(mut events: EventReader<TimerEvent>, mut text: Query<&mut Text, With<ClockText>>) {
+    for event in events.iter() {
+        let color = match event {
+            TimerEvent::Warning(seconds) => {
+                println!("{} seconds left!", seconds);
+                Color::RED
+            }
+            TimerEvent::Extended(_) | TimerEvent::Expired => Color::WHITE,
+        };
+        for mut text in &mut text {
+            text.sections[0].style.color = color;
+        }
+    }
+}
+
+fn spawn_menu_text(commands: &mut Commands, asset_server: &AssetServer, text: String) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 60.0,
+                    color: Color::WHITE,
+                    font: asset_server.load("mrsmonster.ttf"),
+                },
+            )
+            .with_text_alignment(TextAlignment::CENTER),
+        )
+        .insert(Style {
+            align_self: AlignSelf::Center,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Percent(25.0),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(MenuText);
+}
+
+pub fn show_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    slot: Res<SaveSlot>,
+    difficulty: Res<Difficulty>,
+) {
+    let mut text = format!("{}\nPress Enter for a new game\nDifficulty: {:?} (Tab to change)", TITLE, *difficulty);
+    if slot.data.is_some() {
+        text.push_str("\nPress C to continue");
+    }
+    spawn_menu_text(&mut commands, &asset_server, text);
+}
+
+pub fn show_pause(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_menu_text(&mut commands, &asset_server, "Paused\nPress Esc to resume".to_string());
+}
+
+//game over and victory both show how the run went
+pub fn show_run_end(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    stats: Res<RunStats>,
+    state: Res<CurrentState<GameState>>,
+) {
+    let title = if state.0 == GameState::Victory { "You Win!" } else { "Game Over" };
+    let text = format!(
+        "{}\n{}\nTime: {:.0}s\nEnemies killed: {}\nCredits earned: {}\nRooms visited: {}/{}\nPress Enter to play again",
+        title,
+        stats.cause,
+        stats.seconds(),
+        stats.kills,
+        stats.credits_earned,
+        stats.rooms_visited.len(),
+        LAST_ROOM
+    );
+    spawn_menu_text(&mut commands, &asset_server, text);
+}
+
+pub fn clear_menu(mut commands: Commands, menu: Query<Entity, With<MenuText>>) {
+    for e in menu.iter() {
+        commands.entity(e).despawn();
+    }
+}
+
+//Enter starts the game from the main menu and restarts it after it ends, C continues from the save
+pub fn menu_input(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    state: Res<CurrentState<GameState>>,
+    mut continues: EventWriter<ContinueGame>,
+    mut difficulty: ResMut<Difficulty>,
+    mut clock: ResMut<Clock>,
+) {
+    if keys.just_pressed(KeyCode::Return)
+        && matches!(state.0, GameState::MainMenu | GameState::GameOver | GameState::Victory)
+    {
+        commands.insert_resource(NextState(GameState::Playing));
+    } else if keys.just_pressed(KeyCode::C) && state.0 == GameState::MainMenu {
+        continues.send(ContinueGame);
+    } else if keys.just_pressed(KeyCode::Tab) && state.0 == GameState::MainMenu {
+        *difficulty = difficulty.next();
+        clock.restart(difficulty.start_time());
+        //entering the menu again redraws it with the new difficulty
+        commands.insert_resource(NextState(GameState::MainMenu));
+    }
+}
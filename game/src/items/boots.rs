@@ -0,0 +1,21 @@
+use super::*;
+
+//jumps higher
+pub struct Boots;
+
+const BOOTS_JUMP_SPEED: f32 = 15.;
+
+impl Item for Boots {
+    fn name(&self) -> &'static str {
+        "Boots"
+    }
+    fn price(&self) -> Option<i32> {
+        Some(30)
+    }
+    fn texture(&self) -> &'static str {
+        "boots.png"
+    }
+    fn on_activate(&self, pl: &mut ActiveObject, _p: &mut Player, _input: &TickInput) {
+        jump(pl, BOOTS_JUMP_SPEED);
+    }
+}
@@ -0,0 +1,45 @@
+use super::*;
+use bevy::prelude::KeyCode;
+
+//jumps again in midair, each air jump a little weaker than the last
+pub struct DoubleJump;
+
+//speed of each jump after leaving the ground, one entry per air jump
+pub const AIR_JUMP_SPEEDS: [f32; 1] = [8.];
+
+impl Item for DoubleJump {
+    fn name(&self) -> &'static str {
+        "DoubleJump"
+    }
+    fn price(&self) -> Option<i32> {
+        Some(50)
+    }
+    fn texture(&self) -> &'static str {
+        "boots.png"
+    }
+    fn tint(&self) -> Color {
+        Color::CYAN
+    }
+    fn on_activate(&self, pl: &mut ActiveObject, _p: &mut Player, input: &TickInput) {
+        if pl.grounded {
+            pl.velocity.y = JUMP_SPEED;
+            return;
+        }
+        //holding Space only jumps once, each air jump needs a new press
+        if !(input.just_pressed(KeyCode::Space) && air_jump(pl)) {
+            pl.velocity.y += GRAVITY;
+        }
+    }
+}
+
+//uses up the next air jump if there is one left, enemies jump with this too
+pub fn air_jump(obj: &mut ActiveObject) -> bool {
+    match AIR_JUMP_SPEEDS.get(obj.air_jumps as usize) {
+        Some(speed) => {
+            obj.velocity.y = *speed;
+            obj.air_jumps += 1;
+            true
+        }
+        None => false,
+    }
+}
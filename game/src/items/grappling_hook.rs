@@ -0,0 +1,148 @@
+use super::*;
+use crate::line_of_sight::*;
+use bevy::prelude::*;
+
+//hold Space to shoot a rope up and forward, or straight up while holding W, and swing from whatever it hits
+//letting go of Space lets go of the rope, keeping the speed from the swing
+pub struct GrapplingHook;
+
+pub const GRAPPLING_HOOK: &str = "GrapplingHook";
+//how far the hook reaches
+const HOOK_RANGE: f32 = 12. * TILE_SIZE;
+
+impl Item for GrapplingHook {
+    fn name(&self) -> &'static str {
+        GRAPPLING_HOOK
+    }
+    fn price(&self) -> Option<i32> {
+        Some(60)
+    }
+    fn texture(&self) -> &'static str {
+        "bullet.png"
+    }
+    fn tint(&self) -> Color {
+        Color::ORANGE
+    }
+}
+
+//the rope between the player and what the hook caught
+#[derive(Clone, Copy)]
+pub struct Rope {
+    pub anchor: Vec2,
+    pub length: f32,
+    //the object the hook is in, the rope snaps if it breaks
+    pub holding: Entity,
+}
+
+//things the hook can catch on
+fn hookable(o: &Object) -> bool {
+    surface_material(o.obj_type).solid || matches!(o.obj_type, ObjectType::Breakable)
+}
+
+//casts the hook from `from` toward `aim`, catching on the closest thing in range
+pub fn fire_hook<'a>(from: Vec2, aim: Vec2, objects: impl Iterator<Item = (Entity, &'a Object, &'a Transform)>) -> Option<Rope> {
+    let ray = Line::new(from, from + aim.normalize() * HOOK_RANGE, 0);
+    let mut closest: Option<(f32, Entity)> = None;
+    for (e, o, t) in objects {
+        if !hookable(o) {
+            continue;
+        }
+        let edges = box_edges(t.translation.truncate(), o.width, o.height);
+        if let Some(hit) = first_crossing(&ray, &edges) {
+            if closest.map_or(true, |(c, _)| hit < c) {
+                closest = Some((hit, e));
+            }
+        }
+    }
+    closest.map(|(hit, e)| {
+        let anchor = ray.start + (ray.end - ray.start) * hit;
+        Rope {
+            anchor: anchor,
+            length: anchor.distance(from),
+            holding: e,
+        }
+    })
+}
+
+//keeps the player within the rope's length of the anchor, which swings them under gravity
+pub fn swing_on_rope(
+    input: Res<TickInput>,
+    mut player: Query<(&mut ActiveObject, &Transform, &mut Player)>,
+    objects: Query<(Entity, &Object, &Transform), Without<Player>>,
+) {
+    let (mut pl, pt, mut p) = player.single_mut();
+    if p.equipped().name() != GRAPPLING_HOOK || !input.pressed(KeyCode::Space) {
+        p.rope = None;
+        return;
+    }
+    //the rope snaps when whatever it's tied to breaks
+    if let Some(rope) = p.rope {
+        if !objects.get(rope.holding).map_or(false, |(_, o, _)| !o.broken) {
+            println!("The rope snapped!");
+            p.rope = None;
+        }
+    }
+
+    let pos = pt.translation.truncate();
+    if p.rope.is_none() && input.just_pressed(KeyCode::Space) {
+        let aim = if input.pressed(KeyCode::W) {
+            Vec2::Y
+        } else {
+            Vec2::new(if pl.facing_left { -1. } else { 1. }, 1.)
+        };
+        p.rope = fire_hook(pos, aim, objects.iter());
+    }
+
+    if let Some(rope) = p.rope {
+        let offset = pos + pl.velocity - rope.anchor;
+        if offset.length() > rope.length {
+            //pulled back onto the circle around the anchor, whatever speed was going outward is lost
+            let held = rope.anchor + offset.normalize() * rope.length;
+            pl.velocity = held - pos;
+            pl.projected_position = held.extend(pt.translation.z);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct RopeSprite;
+
+pub fn draw_rope(
+    mut commands: Commands,
+    player: Query<(&Transform, &Player)>,
+    mut sprite: Query<(Entity, &mut Transform), (With<RopeSprite>, Without<Player>)>,
+) {
+    let (pt, p) = player.single();
+    let rope = match p.rope {
+        Some(rope) => rope,
+        None => {
+            for (e, _) in sprite.iter() {
+                commands.entity(e).despawn();
+            }
+            return;
+        }
+    };
+    let from = pt.translation.truncate();
+    let span = rope.anchor - from;
+    let transform = Transform {
+        translation: ((from + rope.anchor) / 2.).extend(pt.translation.z - 1.),
+        rotation: Quat::from_rotation_z(span.y.atan2(span.x)),
+        scale: Vec3::new(span.length(), 1., 1.),
+    };
+    match sprite.iter_mut().next() {
+        Some((_, mut t)) => *t = transform,
+        None => {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::BEIGE,
+                        custom_size: Some(Vec2::new(1., 2.)),
+                        ..default()
+                    },
+                    transform: transform,
+                    ..default()
+                })
+                .insert(RopeSprite);
+        }
+    }
+}
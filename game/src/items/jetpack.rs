@@ -0,0 +1,105 @@
+use super::*;
+
+//flies up for as long as Space is held and there is fuel, the tank refills on the ground
+pub struct Jetpack;
+
+pub const JETPACK: &str = "Jetpack";
+const JETPACK_THRUST: f32 = 0.5;
+const JETPACK_MAX_SPEED: f32 = 7.5;
+
+//ticks of thrust a full tank gives, for the starting tank and each upgrade
+pub const FUEL_TANKS: [f32; 3] = [90., 150., 240.];
+//what each upgrade costs on Normal, bought in the shop in order
+pub const FUEL_TANK_PRICES: [i32; 2] = [40, 80];
+//fuel gained per tick standing on the ground
+const FUEL_RECHARGE: f32 = 2.;
+//under this much of a tank the jetpack sputters, only firing every other tick
+const SPUTTER_FRACTION: f32 = 0.25;
+
+impl Item for Jetpack {
+    fn name(&self) -> &'static str {
+        JETPACK
+    }
+    fn price(&self) -> Option<i32> {
+        Some(70)
+    }
+    fn texture(&self) -> &'static str {
+        "jetpack.png"
+    }
+    fn on_activate(&self, pl: &mut ActiveObject, p: &mut Player, _input: &TickInput) {
+        if p.fuel.burn() {
+            if pl.velocity.y < JETPACK_MAX_SPEED {
+                pl.velocity.y += JETPACK_THRUST;
+            }
+        } else if !pl.grounded {
+            pl.velocity.y += GRAVITY;
+        }
+    }
+}
+
+pub struct Fuel {
+    pub amount: f32,
+    //which of FUEL_TANKS the player has
+    pub tank: usize,
+    //running dry overheats the jetpack, and it stays off until the tank is full again
+    pub overheated: bool,
+}
+
+impl Fuel {
+    pub fn new(tank: usize) -> Self {
+        let tank = tank.min(FUEL_TANKS.len() - 1);
+        Self {
+            amount: FUEL_TANKS[tank],
+            tank: tank,
+            overheated: false,
+        }
+    }
+    pub fn capacity(&self) -> f32 {
+        FUEL_TANKS[self.tank]
+    }
+    pub fn fraction(&self) -> f32 {
+        self.amount / self.capacity()
+    }
+    pub fn sputtering(&self) -> bool {
+        !self.overheated && self.fraction() < SPUTTER_FRACTION
+    }
+    pub fn recharge(&mut self) {
+        self.amount = (self.amount + FUEL_RECHARGE).min(self.capacity());
+        if self.amount >= self.capacity() {
+            self.overheated = false;
+        }
+    }
+    //uses a tick of fuel, returns whether the jetpack fires this tick
+    pub fn burn(&mut self) -> bool {
+        if self.overheated {
+            return false;
+        }
+        self.amount -= 1.;
+        if self.amount <= 0. {
+            self.amount = 0.;
+            self.overheated = true;
+            println!("Jetpack overheated!");
+            return false;
+        }
+        !self.sputtering() || (self.amount as u32) % 2 == 0
+    }
+    //price of the next tank, None once fully upgraded
+    pub fn next_tank_price(&self) -> Option<i32> {
+        FUEL_TANK_PRICES.get(self.tank).copied()
+    }
+    pub fn upgrade(&mut self) {
+        *self = Fuel::new(self.tank + 1);
+    }
+}
+
+//upgrades are unlocked like items, so every run after buying one starts with the bigger tank
+pub fn fuel_tank_unlock(tank: usize) -> String {
+    format!("FuelTank{}", tank)
+}
+
+pub fn unlocked_tank(unlocks: &[String]) -> usize {
+    (1..FUEL_TANKS.len())
+        .filter(|tank| unlocks.contains(&fuel_tank_unlock(*tank)))
+        .max()
+        .unwrap_or(0)
+}
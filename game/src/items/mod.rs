@@ -0,0 +1,76 @@
+//every item the player can equip, one module each
+//an item is added by writing its module and listing it in ITEMS, the shop and the J toggle pick it up from there
+use crate::active_util::*;
+use crate::fixed_step::*;
+use crate::util::*;
+use bevy::prelude::Color;
+
+pub mod boots;
+pub mod double_jump;
+pub mod grappling_hook;
+pub mod jetpack;
+pub mod no_item;
+pub mod umbrella;
+
+use boots::Boots;
+use double_jump::DoubleJump;
+use grappling_hook::GrapplingHook;
+use jetpack::Jetpack;
+use no_item::NoItem;
+use umbrella::Umbrella;
+
+//how fast a plain jump leaves the ground
+pub const JUMP_SPEED: f32 = 10.;
+
+pub trait Item: Sync {
+    //the name it is saved and unlocked under
+    fn name(&self) -> &'static str;
+    //what it costs on Normal, None if the shop does not sell it
+    fn price(&self) -> Option<i32> {
+        None
+    }
+    //sprite shown for it in the shop
+    fn texture(&self) -> &'static str {
+        ""
+    }
+    //color the shop sprite is drawn in, for items sharing a texture
+    fn tint(&self) -> Color {
+        Color::WHITE
+    }
+    //when J switches to it
+    fn on_equip(&self, _pl: &mut ActiveObject) {}
+    //when J switches away from it
+    fn on_unequip(&self, _pl: &mut ActiveObject) {}
+    //every tick it is equipped, before it jumps or falls
+    fn on_tick(&self, _pl: &mut ActiveObject, _p: &mut Player, _input: &TickInput) {}
+    //every tick Space is held while it is equipped, a plain jump unless the item changes it
+    fn on_activate(&self, pl: &mut ActiveObject, _p: &mut Player, _input: &TickInput) {
+        jump(pl, JUMP_SPEED);
+    }
+    //every tick Space is not held while it is equipped, plain gravity unless the item changes it
+    fn on_fall(&self, pl: &mut ActiveObject, _p: &mut Player, _input: &TickInput) {
+        if !pl.grounded {
+            pl.velocity.y += GRAVITY;
+        }
+    }
+}
+
+//jumps off the ground, or keeps falling when already in the air
+pub fn jump(pl: &mut ActiveObject, speed: f32) {
+    if pl.grounded {
+        pl.velocity.y = speed;
+    } else {
+        pl.velocity.y += GRAVITY;
+    }
+}
+
+//in the order J cycles through them and the shop shows them
+pub static ITEMS: [&dyn Item; 6] = [&NoItem, &Umbrella, &Boots, &Jetpack, &DoubleJump, &GrapplingHook];
+
+pub fn find_item(name: &str) -> Option<&'static dyn Item> {
+    ITEMS.iter().copied().find(|item| item.name() == name)
+}
+
+pub fn items_for_sale() -> impl Iterator<Item = &'static dyn Item> {
+    ITEMS.iter().copied().filter(|item| item.price().is_some())
+}
@@ -0,0 +1,12 @@
+use super::*;
+
+//the empty slot every player starts with, jumps and falls normally
+pub struct NoItem;
+
+pub const NO_ITEM: &str = "None";
+
+impl Item for NoItem {
+    fn name(&self) -> &'static str {
+        NO_ITEM
+    }
+}
@@ -0,0 +1,39 @@
+use super::*;
+use bevy::prelude::KeyCode;
+
+//opens on the way down to fall slowly, holding S closes it
+pub struct Umbrella;
+
+impl Item for Umbrella {
+    fn name(&self) -> &'static str {
+        "Umbrella"
+    }
+    fn price(&self) -> Option<i32> {
+        Some(30)
+    }
+    fn texture(&self) -> &'static str {
+        "umbrella.png"
+    }
+    fn on_activate(&self, pl: &mut ActiveObject, _p: &mut Player, _input: &TickInput) {
+        if pl.grounded {
+            pl.velocity.y = JUMP_SPEED;
+        } else if pl.velocity.y <= UMBRELLA_VELOCITY {
+            //open umbrella when going down
+            pl.velocity.y = UMBRELLA_VELOCITY;
+        } else {
+            pl.velocity.y += GRAVITY;
+        }
+    }
+    fn on_fall(&self, pl: &mut ActiveObject, _p: &mut Player, input: &TickInput) {
+        if pl.grounded {
+            return;
+        }
+        if input.pressed(KeyCode::S) || pl.velocity.y > UMBRELLA_VELOCITY {
+            //if they press down, they can close the umbrella
+            pl.velocity.y += GRAVITY;
+        } else {
+            //open umbrella when going down
+            pl.velocity.y = UMBRELLA_VELOCITY;
+        }
+    }
+}
@@ -0,0 +1,818 @@
+use crate::abilities::*;
+use crate::active_util::*;
+use crate::ai::*;
+use crate::fixed_step::*;
+use crate::difficulty::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::items::jetpack::*;
+use crate::items::*;
+use crate::movement_mesh::*;
+use crate::physics::*;
+use crate::replay::*;
+use crate::status_effects::*;
+use crate::timer::*;
+use crate::util::*;
+use bevy::prelude::*;
+use bevy::render::mesh::Indices;
+use bevy::render::render_resource::PrimitiveTopology;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy::tasks::AsyncComputeTaskPool;
+use bevy::utils::HashMap;
+use iyes_loopless::prelude::*;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+//seconds on the run clock on Normal
+pub const START_TIME: f32 = 100.;
+pub const CREDIT_SZ: f32 = 16.;
+
+//sent whenever the game should be saved
+pub struct SaveGame;
+
+//which entry in its room's level data a level object was spawned from
+#[derive(Component)]
+pub struct LevelIndex(pub i32);
+
+//everything needed to spawn a room, built away from the main thread so changing rooms does not hitch
+#[derive(Clone)]
+pub struct RoomData {
+    pub room: i8,
+    pub level: Vec<Descriptor>,
+    pub platforms: Vec<PlatformDescriptor>,
+    pub spawns: Vec<SpawnMarker>,
+    pub mesh: Graph,
+}
+
+impl RoomData {
+    pub fn build(room: i8) -> Self {
+        let level = get_level(room);
+        let platforms = get_level_platforms(room);
+        let mut mesh = get_level_mesh(room);
+        add_one_way_edges(&mut mesh, &level);
+        add_wall_jump_edges(&mut mesh, &level);
+        for (a, b) in check_walk_edges(&mesh, &level) {
+            warn!("Walking edge {} -> {} in room {} runs into a wall", a, b, room);
+        }
+        //moving platforms get edges for riding them
+        for plat in &platforms {
+            add_platform_edges(&mut mesh, plat);
+        }
+        Self {
+            room: room,
+            level: level,
+            platforms: platforms,
+            spawns: get_level_spawns(room),
+            mesh: mesh,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TransitionStyle {
+    Fade,
+    Slide,
+}
+
+//how the screen changes over between rooms, and the rooms built or being built in the background
+pub struct RoomTransition {
+    pub style: TransitionStyle,
+    //length of each half of a transition, 0 swaps the rooms as soon as the new one is built
+    pub seconds: f32,
+    //start building the rooms next door as soon as a room is entered
+    pub preload: bool,
+    //the room being changed to, None when no transition is running
+    pub target: Option<i8>,
+    //how much of the screen is covered, from 0 to 1
+    pub cover: f32,
+    pub covering: bool,
+    //1 when the player left through the right side of the room, -1 for the left
+    pub direction: f32,
+    built: HashMap<i8, RoomData>,
+    building: Vec<i8>,
+    sender: Mutex<Sender<RoomData>>,
+    receiver: Mutex<Receiver<RoomData>>,
+}
+
+impl RoomTransition {
+    pub fn new(style: TransitionStyle, seconds: f32, preload: bool) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            style: style,
+            seconds: seconds,
+            preload: preload,
+            target: None,
+            cover: 0.,
+            covering: false,
+            direction: 1.,
+            built: HashMap::default(),
+            building: Vec::new(),
+            sender: Mutex::new(sender),
+            receiver: Mutex::new(receiver),
+        }
+    }
+    pub fn active(&self) -> bool {
+        self.target.is_some()
+    }
+    fn begin(&mut self, room: i8, direction: f32) {
+        self.target = Some(room);
+        self.cover = 0.;
+        self.covering = true;
+        self.direction = direction;
+        self.load(room);
+    }
+    //starts building `room` in the background unless it is built or being built already
+    pub fn load(&mut self, room: i8) {
+        if self.built.contains_key(&room) || self.building.contains(&room) {
+            return;
+        }
+        self.building.push(room);
+        let sender = self.sender.lock().unwrap().clone();
+        AsyncComputeTaskPool::get()
+            .spawn(async move {
+                let _ = sender.send(RoomData::build(room));
+            })
+            .detach();
+    }
+    //the built room, or None if it is still being built
+    //rooms are built the same way every time, so a built room gets kept for later visits
+    fn try_take(&mut self, room: i8) -> Option<RoomData> {
+        self.load(room);
+        let receiver = self.receiver.lock().unwrap();
+        while let Ok(data) = receiver.try_recv() {
+            self.building.retain(|r| *r != data.room);
+            self.built.insert(data.room, data);
+        }
+        self.built.get(&room).cloned()
+    }
+}
+
+//level objects destroyed in each room, so rooms stay how they were left until the clock runs out or the run ends
+//dead enemies stay dead, so the credits they give cannot be collected twice
+pub struct RoomMemory {
+    gone: HashMap<i8, Vec<i32>>,
+    //level indices spawned into the current room
+    spawned: Vec<i32>,
+    //credits dropped in each room and not picked up yet, they have no level index so they are kept by position
+    dropped: HashMap<i8, Vec<Vec2>>,
+}
+
+impl RoomMemory {
+    pub fn new() -> Self {
+        Self {
+            gone: HashMap::default(),
+            spawned: Vec::new(),
+            dropped: HashMap::default(),
+        }
+    }
+    pub fn clear(&mut self) {
+        self.gone.clear();
+        self.spawned.clear();
+        self.dropped.clear();
+    }
+    pub fn is_gone(&self, room: i8, index: i32) -> bool {
+        self.gone.get(&room).map_or(false, |gone| gone.contains(&index))
+    }
+    //anything spawned into `room` that is not in `remaining` is gone for the rest of the run
+    //and the credits still lying in it are there again on the next visit
+    fn leave(&mut self, room: i8, remaining: impl Iterator<Item = i32>, dropped: impl Iterator<Item = Vec2>) {
+        self.dropped.insert(room, dropped.collect());
+        let remaining: Vec<i32> = remaining.collect();
+        let gone = self.gone.entry(room).or_default();
+        for index in self.spawned.drain(..) {
+            if !remaining.contains(&index) && !gone.contains(&index) {
+                gone.push(index);
+            }
+        }
+    }
+    fn take_dropped(&mut self, room: i8) -> Vec<Vec2> {
+        self.dropped.remove(&room).unwrap_or_default()
+    }
+}
+
+//spawns the player and loads rooms
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<RoomTransition>() {
+            app.insert_resource(RoomTransition::new(TransitionStyle::Fade, 0.25, true));
+        }
+        app.add_event::<SaveGame>()
+            .insert_resource(RoomMemory::new())
+            .add_startup_system(setup_world)
+            //room changes happen last so the next tick starts in the new room
+            .add_gameplay_system(create_level.run_in_state(GameState::Playing).label(Step::Level).label(GameplaySystem::CreateLevel).after(GameplaySystem::TickClock))
+            //the swap itself runs every frame, not every tick, since nothing simulates during it
+            .add_system(run_transition.run_in_state(GameState::Transition))
+            //restarting after the run ends starts over from scratch
+            .add_exit_system(GameState::GameOver, reset_run)
+            .add_exit_system(GameState::Victory, reset_run);
+    }
+}
+
+//starts changing rooms once a teleporter, the clock or a new run has picked a different room
+pub fn create_level(
+    mut commands: Commands,
+    mut manager: Query<&mut Manager, (With<Manager>)>,
+    player: Query<&Transform, With<Player>>,
+    objects: Query<(&LevelIndex, &Object)>,
+    drops: Query<(&Transform, &Object), Without<LevelIndex>>,
+    mut memory: ResMut<RoomMemory>,
+    mut transition: ResMut<RoomTransition>,
+    mut saves: EventWriter<SaveGame>,
+) {
+    let mut m = manager.single_mut();
+    //the state only changes over to Transition after this frame's ticks, so one may already be starting
+    if m.room_number == m.prev_room_number || transition.active() {
+        return;
+    }
+
+    //whatever got destroyed in the room being left stays destroyed
+    memory.leave(
+        m.prev_room_number,
+        objects.iter().filter(|(_, o)| !o.broken).map(|(i, _)| i.0),
+        drops.iter().filter(|(_, o)| matches!(o.obj_type, ObjectType::Credit)).map(|(t, _)| t.translation.truncate()),
+    );
+    m.prev_room_number = m.room_number;
+    //every room transition is a checkpoint
+    saves.send(SaveGame);
+
+    let direction = if player.single().translation.x > 0. { 1. } else { -1. };
+    transition.begin(m.room_number, direction);
+    commands.insert_resource(NextState(GameState::Transition));
+}
+
+//covers the screen, swaps the rooms once it is covered and the new room is built, then uncovers it
+pub fn run_transition(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut transition: ResMut<RoomTransition>,
+    mut memory: ResMut<RoomMemory>,
+    manager: Query<&Manager>,
+    mut player_query: Query<(&mut Transform, &mut ActiveObject), (With<Player>)>,
+    query: Query<Entity, (With<Object>, Without<Player>)>,
+    graph_query: Query<Entity, (With<GraphNode>)>,
+    mesh_query: Query<Entity, (With<Graph>)>,
+    mut clock: ResMut<Clock>,
+    mut platform_clock: ResMut<PlatformClock>,
+    difficulty: Res<Difficulty>,
+) {
+    let room = match transition.target {
+        Some(room) => room,
+        None => return,
+    };
+    let step = if transition.seconds > 0. {
+        time.delta_seconds() / transition.seconds
+    } else {
+        1.
+    };
+
+    if transition.covering {
+        transition.cover = (transition.cover + step).min(1.);
+        if transition.cover < 1. {
+            return;
+        }
+        //the screen stays covered until the room is built
+        let data = match transition.try_take(room) {
+            Some(data) => data,
+            None => return,
+        };
+        transition.covering = false;
+
+        for e_ in query.iter() {
+            commands.entity(e_).despawn();
+        }
+        for g_ in graph_query.iter() {
+            commands.entity(g_).despawn();
+        }
+        let m_ = mesh_query.single();
+        commands.entity(m_).despawn();
+
+        //the player arrives at the marker the teleporter named, or the room's first marker if it has no such marker
+        let (mut p, mut pl) = player_query.single_mut();
+        let m = manager.single();
+        match data.spawns.iter().find(|s| s.name == m.spawn).or(data.spawns.first()) {
+            Some(spawn) => {
+                if spawn.name != m.spawn {
+                    warn!("Room {} has no spawn marker {}", room, m.spawn);
+                }
+                p.translation = Vec3::new(spawn.x_pos, spawn.y_pos, p.translation.z);
+                if !spawn.keep_velocity {
+                    pl.velocity = Vec2::ZERO;
+                }
+            }
+            None => warn!("Room {} has no spawn markers", room),
+        }
+
+        if room == 0 {
+            let mut prices: Vec<String> = items_for_sale().map(|item| format!("{}: {}", item.name(), difficulty.price(item.price().unwrap()))).collect();
+            prices.push(format!("Extra Time: {}", difficulty.price(EXTRA_TIME_PRICE)));
+            let tanks: Vec<String> = FUEL_TANK_PRICES.iter().map(|price| difficulty.price(*price).to_string()).collect();
+            prices.push(format!("Fuel Tank Upgrades: {}", tanks.join(" / ")));
+            info!("Shop: press B under an item to buy it. Prices in credits: {}", prices.join(", "));
+        } else {
+            clock.timer.unpause();
+        }
+        if transition.preload {
+            for desc in data.level.iter().filter(|d| matches!(d.obj_type, ObjectType::Teleporter)) {
+                transition.load(desc.level);
+            }
+        }
+        spawn_room(&mut commands, &asset_server, &mut meshes, &mut materials, &mut platform_clock, &mut memory, &difficulty, data);
+        //an instant transition uncovers in the same frame
+        if transition.seconds > 0. {
+            return;
+        }
+    }
+
+    transition.cover = (transition.cover - step).max(0.);
+    if transition.cover == 0. {
+        transition.target = None;
+        commands.insert_resource(NextState(GameState::Playing));
+    }
+}
+
+//spawns everything in a room that was not destroyed earlier in the run
+fn spawn_room(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    platform_clock: &mut PlatformClock,
+    memory: &mut RoomMemory,
+    difficulty: &Difficulty,
+    data: RoomData,
+) {
+    let preset = difficulty.preset();
+    let retreat_health = (preset.enemy_health as f32 * preset.retreat_fraction) as i32;
+    let mut id = 0;
+    for desc in data.level {
+        if memory.is_gone(data.room, id) {
+            id += 1;
+            continue;
+        }
+        memory.spawned.push(id);
+        let mut texture_path = "";
+        if !matches!(desc.obj_type, ObjectType::Block) {
+            // conditionally render object textures
+            if matches!(desc.obj_type, ObjectType::Cobweb) {
+                texture_path = "spiderweb.png";
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        texture: asset_server.load(texture_path),
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(
+                desc.obj_type,
+                ObjectType::Ice | ObjectType::BouncePad | ObjectType::Goo
+            ) {
+                let color = match desc.obj_type {
+                    ObjectType::Ice => Color::CYAN,
+                    ObjectType::BouncePad => Color::LIME_GREEN,
+                    _ => Color::OLIVE,
+                };
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: color,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::Spike) {
+                texture_path = "spike.png";
+            } else if matches!(desc.obj_type, ObjectType::Item) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::GREEN,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::TimePickup | ObjectType::WallJumpPickup) {
+                let color = if matches!(desc.obj_type, ObjectType::TimePickup) { Color::GOLD } else { Color::PURPLE };
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: color,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if let Some(ratio) = slope_ratio(desc.obj_type) {
+                //slopes are drawn as a triangle with the tall side on the high end
+                let (w, h) = (desc.width / 2., desc.height / 2.);
+                let high_x = if ratio > 0. { w } else { -w };
+                let mut triangle = Mesh::new(PrimitiveTopology::TriangleList);
+                triangle.insert_attribute(
+                    Mesh::ATTRIBUTE_POSITION,
+                    vec![[-w, -h, 0.], [w, -h, 0.], [high_x, h, 0.]],
+                );
+                triangle.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0., 0., 1.]; 3]);
+                triangle.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0., 0.]; 3]);
+                triangle.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+                commands
+                    .spawn_bundle(MaterialMesh2dBundle {
+                        mesh: meshes.add(triangle).into(),
+                        material: materials.add(ColorMaterial::from(Color::WHITE)),
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::OneWayPlatform) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::BEIGE,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::Teleporter) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::GREEN,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new2(
+                        id,
+                        desc.width,
+                        desc.height,
+                        desc.obj_type,
+                        desc.level,
+                        desc.spawn,
+                    ));
+            } else if matches!(desc.obj_type, ObjectType::UmbrellaItem) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            //color: Color::PURPLE,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        texture: asset_server.load("umbrella.png"),
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::JetpackItem) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            //color: Color::GRAY,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        texture: asset_server.load("jetpack.png"),
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::Barrel) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            //color: Color::GRAY,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        texture: asset_server.load("explosiveBarrel.png"),
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(ActiveObject::new())
+                    .insert(MovableObject)
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::Breakable) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        texture: asset_server.load("breakable.png"),
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(ActiveObject::new())
+                    .insert(MovableObject)
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+            } else if matches!(desc.obj_type, ObjectType::MeleeEnemy) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::RED,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 5.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(ActiveObject::new())
+                    .insert(Health::new(preset.enemy_health))
+                    .insert(StatusEffects::new())
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(
+                        900,
+                        desc.width,
+                        desc.height,
+                        ObjectType::MeleeEnemy,
+                    ))
+                    .insert(Enemy::new(Type::Melee, preset.enemy_health, retreat_health));
+            } else if matches!(desc.obj_type, ObjectType::RangedEnemy) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::PURPLE,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 5.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(ActiveObject::new())
+                    .insert(Health::new(preset.enemy_health))
+                    .insert(StatusEffects::new())
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(
+                        900,
+                        desc.width,
+                        desc.height,
+                        ObjectType::RangedEnemy,
+                    ))
+                    .insert(Enemy::new(Type::Ranged, preset.enemy_health, retreat_health));
+            } else if matches!(desc.obj_type, ObjectType::OtherEnemy) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::YELLOW,
+                            custom_size: Some(Vec2::new(desc.width, desc.height)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(desc.x_pos, desc.y_pos, 5.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(ActiveObject::new())
+                    .insert(Health::new(preset.enemy_health))
+                    .insert(StatusEffects::new())
+                    .insert(LevelIndex(id))
+                    .insert(Object::new(
+                        900,
+                        desc.width,
+                        desc.height,
+                        ObjectType::OtherEnemy,
+                    ))
+                    .insert(Enemy::new(Type::Hybrid, preset.enemy_health, retreat_health));
+            }
+        } else {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::new(desc.width, desc.height)),
+                        ..default()
+                    },
+                    transform: Transform {
+                        translation: Vec3::new(desc.x_pos, desc.y_pos, 2.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(LevelIndex(id))
+                .insert(Object::new(id, desc.width, desc.height, desc.obj_type));
+        }
+        id += 1;
+    }
+
+    //moving platforms get spawned at their first waypoint, the mesh gets edges for riding them
+    platform_clock.ticks = 0;
+    for plat in data.platforms {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::GRAY,
+                    custom_size: Some(Vec2::new(plat.width, plat.height)),
+                    ..default()
+                },
+                transform: Transform {
+                    translation: plat.waypoints[0].extend(2.),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Object::new(id, plat.width, plat.height, ObjectType::MovingPlatform))
+            .insert(MovingPlatform::new(&plat));
+        id += 1;
+    }
+
+    for pos in memory.take_dropped(data.room) {
+        spawn_credit(commands, pos);
+    }
+
+    for v in data.mesh.vertices.clone() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::ORANGE,
+                    custom_size: Some(Vec2::new(5., 5.)),
+                    ..default()
+                },
+                //   texture: asset_server.load("explosiveBarrel.png"),
+                transform: Transform {
+                    translation: Vec3::new(v.x, v.y, 2.),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(GraphNode);
+    }
+
+    commands.spawn().insert(data.mesh);
+}
+
+//a credit lying in the room, dropped by a killed enemy and picked up by touching it
+pub fn spawn_credit(commands: &mut Commands, pos: Vec2) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::YELLOW,
+                custom_size: Some(Vec2::splat(CREDIT_SZ)),
+                ..default()
+            },
+            transform: Transform {
+                translation: pos.extend(2.),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Object::new(-1, CREDIT_SZ, CREDIT_SZ, ObjectType::Credit));
+}
+
+//everything the simulation needs, the rooms themselves get spawned by create_level
+pub fn setup_world(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    replay: Res<Replay>,
+    difficulty: Res<Difficulty>,
+    progress: Res<Progress>,
+) {
+    commands.insert_resource(Clock::new(difficulty.start_time()));
+    commands.insert_resource(PlatformClock::new());
+
+    //Player(spawns slightly above origin now, starting tile of map centered on origin.)
+    let pt = Transform {
+        translation: Vec3::new(0., 320., 900.),
+        ..default()
+    };
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                // color: Color::BLUE,
+                custom_size: Some(Vec2::new(PLAYER_SZ, PLAYER_SZ)),
+                ..default()
+            },
+            texture: asset_server.load("vampire1.png"),
+            transform: pt,
+            ..default()
+        })
+        .insert(ActiveObject::new())
+        .insert(Health::new(PLAYER_HEALTH))
+        .insert(Object::new(-1, PLAYER_SZ, PLAYER_SZ, ObjectType::Player))
+        .insert(StatusEffects::new())
+        .insert(unlocked_player(&progress.unlocks));
+    //this variable can change based on what room the player is in
+    //let mut level = get_level(1);
+    //let mesh = get_level_mesh(1);
+
+    
+    commands.spawn().insert(Manager::new(-1, replay.start_room));
+    let graph = Graph::new();
+    commands.spawn().insert(graph);
+    //create_level(commands, asset_server, texture_atlases, level, mesh, 1);
+}
+
+//a new run's player, with everything unlocked in earlier runs and sessions
+pub fn unlocked_player(unlocks: &[String]) -> Player {
+    let mut p = Player::new();
+    apply_unlocks(&mut p, unlocks);
+    p
+}
+
+//gives the player the items, abilities and fuel tank that `unlocks` holds
+pub fn apply_unlocks(p: &mut Player, unlocks: &[String]) {
+    for name in unlocks {
+        if let Some(item) = find_item(name) {
+            if !p.items.contains(&item.name()) {
+                p.items.push(item.name());
+            }
+        }
+    }
+    p.abilities = unlocked_abilities(unlocks);
+    p.fuel = Fuel::new(unlocked_tank(unlocks));
+}
+
+//puts the player, the clock and the stats back to how a new game starts, keeping unlocked items
+pub fn reset_run(
+    mut player: Query<(&mut Player, &mut ActiveObject, &mut Health, &mut StatusEffects), With<Player>>,
+    mut manager: Query<&mut Manager, (With<Manager>)>,
+    mut clock: ResMut<Clock>,
+    mut stats: ResMut<RunStats>,
+    mut memory: ResMut<RoomMemory>,
+    progress: Res<Progress>,
+    replay: Res<Replay>,
+    difficulty: Res<Difficulty>,
+) {
+    let (mut p, mut pl, mut health, mut effects) = player.single_mut();
+    *p = unlocked_player(&progress.unlocks);
+    effects.clear();
+    *pl = ActiveObject::new();
+    *health = Health::new(PLAYER_HEALTH);
+    let mut m = manager.single_mut();
+    //create_level reloads the room since prev_room_number no longer matches, and puts the player at its start
+    m.prev_room_number = -1;
+    m.room_number = replay.start_room;
+    m.spawn = "start";
+    clock.restart(difficulty.start_time());
+    memory.clear();
+    *stats = RunStats::new();
+}
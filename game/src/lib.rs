@@ -0,0 +1,41 @@
+//all of the game's logic, main.rs only puts the plugins together with a window
+pub mod abilities;
+pub mod active_util;
+pub mod ai;
+pub mod collisions;
+pub mod difficulty;
+pub mod enemies;
+pub mod fixed_step;
+pub mod game_state;
+pub mod harness;
+pub mod health;
+pub mod hud;
+pub mod items;
+pub mod level;
+pub mod line_of_sight;
+pub mod map;
+pub mod melee;
+pub mod movement_mesh;
+pub mod physics;
+pub mod player;
+pub mod replay;
+pub mod save;
+pub mod shop;
+pub mod status_effects;
+pub mod timer;
+pub mod util;
+pub mod weapons;
+
+pub use crate::enemies::AiPlugin;
+pub use crate::fixed_step::FixedStepPlugin;
+pub use crate::health::HealthPlugin;
+pub use crate::game_state::GameStatePlugin;
+pub use crate::hud::HudPlugin;
+pub use crate::level::LevelPlugin;
+pub use crate::map::MapPlugin;
+pub use crate::physics::PhysicsPlugin;
+pub use crate::player::PlayerPlugin;
+pub use crate::save::SavePlugin;
+pub use crate::shop::ShopPlugin;
+pub use crate::status_effects::StatusPlugin;
+pub use crate::timer::TimerPlugin;
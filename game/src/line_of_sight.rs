@@ -3,6 +3,7 @@ use crate::movement_mesh::*;
 use crate::ai::*;
 use crate::util::*;
 use crate::active_util::*;
+use crate::difficulty::*;
 
 #[derive(Component)]
 pub struct Line {
@@ -112,6 +113,45 @@ pub fn find_vertices(x1:f32, y1:f32, x2:f32, y2:f32, width:f32, height:f32) -> (
     return (v1, v2, v3);
 }
 
+//the four sides of a box, for testing lines against it
+pub fn box_edges(center: Vec2, width: f32, height: f32) -> [Line; 4] {
+    let (w, h) = (width / 2., height / 2.);
+    let corners = [
+        center + Vec2::new(-w, h),
+        center + Vec2::new(w, h),
+        center + Vec2::new(w, -h),
+        center + Vec2::new(-w, -h),
+    ];
+    [
+        Line::new(corners[0], corners[1], 0),
+        Line::new(corners[1], corners[2], 1),
+        Line::new(corners[2], corners[3], 2),
+        Line::new(corners[3], corners[0], 3),
+    ]
+}
+
+//how far along `ray` it first crosses one of `edges`, from 0 at its start to 1 at its end
+//narrowed down by testing shorter and shorter pieces of the ray, since lines_intersect only says yes or no
+pub fn first_crossing(ray: &Line, edges: &[Line]) -> Option<f32> {
+    let crosses = |t: f32| {
+        let piece = Line::new(ray.start, ray.start + (ray.end - ray.start) * t, ray.id);
+        edges.iter().any(|e| lines_intersect(&piece, e))
+    };
+    if !crosses(1.) {
+        return None;
+    }
+    let (mut lo, mut hi) = (0., 1.);
+    for _ in 0..16 {
+        let mid = (lo + hi) / 2.;
+        if crosses(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Some(hi)
+}
+
 pub fn distance_squared(x1: f32, y1: f32, x2: f32, y2:f32) -> f32 {
     (x1 - x2) * (x1 - x2) + (y1 - y2) * (y1 - y2)
 }
@@ -121,8 +161,9 @@ pub fn calculate_sight(
     //player: Query<(&Object, &Transform), (With<ActiveObject>, With<Player>)>,
     mut enemies: Query<(&Transform, &mut Enemy), (With<ActiveObject>, With<Enemy>)>,
     objects: Query<(&Object, &Transform), With<Object>>,
+    difficulty: Res<Difficulty>,
 ) {
-    let sight_distance = 800.0;
+    let sight_distance = difficulty.preset().sight_distance;
 
     for (tr, mut en) in enemies.iter_mut() {
         let pos = tr.translation;
@@ -133,8 +174,8 @@ pub fn calculate_sight(
         for (o, t) in objects.iter() {
             //v1 and v2 and v3 hold the three vertices visible to the player
             match o.obj_type {
-                ObjectType::Block | ObjectType::Spike => {
-                    //blocks and spikes are the only two objects that block line of sight
+                ObjectType::Block | ObjectType::Spike | ObjectType::Ice | ObjectType::BouncePad | ObjectType::Goo => {
+                    //blocks, spikes and special floors are the only objects that block line of sight
                     let (v1, v2, v3) = find_vertices(
                         pos.x,
                         pos.y,
@@ -155,6 +196,9 @@ pub fn calculate_sight(
                 ObjectType::Bullet => {
                     //enemy will avoid these, or not, who cares
                 }
+                ObjectType::OneWayPlatform => {
+                    //one way platforms are thin enough to see past, so they don't block line of sight
+                }
                 ObjectType::MeleeEnemy => {
                     let sight_line = Line::new(
                         Vec2::new(pos.x, pos.y),
@@ -0,0 +1,256 @@
+use crate::active_util::*;
+use crate::game_state::*;
+use crate::util::*;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+//size of one room on the full map and the space between rooms
+const CELL_W: f32 = 96.;
+const CELL_H: f32 = 54.;
+const CELL_GAP: f32 = 32.;
+//the minimap shows only the current room, in the bottom right corner above the clock
+const MINIMAP_W: f32 = 192.;
+const MINIMAP_H: f32 = 106.;
+
+//a room and the rooms its teleporters lead to
+pub struct RoomNode {
+    pub id: i8,
+    pub exits: Vec<i8>,
+    //grid cell on the map, rooms to the left of a room sit one cell left of it
+    pub cell: IVec2,
+}
+
+//how the rooms connect, built from the teleporters in each room's level data
+pub struct WorldMap {
+    pub rooms: Vec<RoomNode>,
+}
+
+impl WorldMap {
+    pub fn build() -> Self {
+        let mut rooms: Vec<RoomNode> = Vec::new();
+        let mut sides: Vec<Vec<(i8, i32)>> = Vec::new();
+        for id in 0..=LAST_ROOM {
+            let mut exits = Vec::new();
+            let mut side = Vec::new();
+            for desc in get_level(id) {
+                if matches!(desc.obj_type, ObjectType::Teleporter) && !exits.contains(&desc.level) {
+                    exits.push(desc.level);
+                    side.push((desc.level, if desc.x_pos < 0. { -1 } else { 1 }));
+                }
+            }
+            rooms.push(RoomNode {
+                id: id,
+                exits: exits,
+                cell: IVec2::ZERO,
+            });
+            sides.push(side);
+        }
+
+        //lay the rooms out going out from room 1, following which wall each teleporter is on
+        let mut placed: Vec<Option<IVec2>> = vec![None; rooms.len()];
+        placed[1] = Some(IVec2::ZERO);
+        let mut queue = vec![1usize];
+        while let Some(r) = queue.pop() {
+            let cell = placed[r].unwrap();
+            for (to, dx) in &sides[r] {
+                let to = *to as usize;
+                if to >= placed.len() || placed[to].is_some() {
+                    continue;
+                }
+                //two rooms on the same side would overlap, so the second goes below
+                let mut next = cell + IVec2::new(*dx, 0);
+                while placed.contains(&Some(next)) {
+                    next.y -= 1;
+                }
+                placed[to] = Some(next);
+                queue.insert(0, to);
+            }
+        }
+        //rooms nothing leads to, like the shop, go on a row underneath
+        let mut x = 0;
+        for cell in placed.iter_mut() {
+            if cell.is_none() {
+                *cell = Some(IVec2::new(x, -1));
+                x += 1;
+            }
+        }
+        for room in rooms.iter_mut() {
+            room.cell = placed[room.id as usize].unwrap();
+        }
+        Self { rooms: rooms }
+    }
+
+    pub fn room(&self, id: i8) -> Option<&RoomNode> {
+        self.rooms.iter().find(|r| r.id == id)
+    }
+}
+
+#[derive(Component)]
+struct FullMap;
+
+//holds the current room's solid tiles on the minimap, `room` is the room they were drawn for
+#[derive(Component)]
+struct MinimapTiles {
+    room: i8,
+}
+
+//a dot standing in for the player, placed inside a room drawn at `origin` with size `size`
+#[derive(Component)]
+struct MapDot {
+    origin: Vec2,
+    size: Vec2,
+}
+
+//M opens and closes the full map, the minimap is always up
+pub struct MapPlugin;
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WorldMap::build())
+            .add_startup_system(spawn_minimap)
+            .add_system(toggle_world_map.run_in_state(GameState::Playing))
+            .add_system(draw_minimap_room.run_in_state(GameState::Playing))
+            .add_system(move_map_dots);
+    }
+}
+
+fn node(left: f32, bottom: f32, w: f32, h: f32, color: Color) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(left),
+                bottom: Val::Px(bottom),
+                ..default()
+            },
+            size: Size::new(Val::Px(w), Val::Px(h)),
+            ..default()
+        },
+        color: UiColor(color),
+        ..default()
+    }
+}
+
+fn spawn_dot(parent: &mut ChildBuilder, origin: Vec2, size: Vec2) {
+    parent
+        .spawn_bundle(node(origin.x, origin.y, 6., 6., Color::RED))
+        .insert(MapDot {
+            origin: origin,
+            size: size,
+        });
+}
+
+fn spawn_minimap(mut commands: Commands) {
+    let origin = Vec2::new(WIN_W - MINIMAP_W - 15., 110.);
+    commands
+        .spawn_bundle(node(origin.x, origin.y, MINIMAP_W, MINIMAP_H, Color::rgba(0., 0., 0., 0.6)))
+        .with_children(|parent| {
+            //children are placed relative to the minimap, and the tiles go first so the dot is drawn over them
+            parent
+                .spawn_bundle(node(0., 0., MINIMAP_W, MINIMAP_H, Color::NONE))
+                .insert(MinimapTiles { room: -1 });
+            spawn_dot(parent, Vec2::ZERO, Vec2::new(MINIMAP_W, MINIMAP_H));
+        });
+}
+
+//redraws the minimap's tiles whenever the player ends up in a different room
+fn draw_minimap_room(mut commands: Commands, manager: Query<&Manager>, mut tiles: Query<(Entity, &mut MinimapTiles)>) {
+    let room = manager.single().room_number;
+    let (e, mut drawn) = tiles.single_mut();
+    if drawn.room == room {
+        return;
+    }
+    drawn.room = room;
+    commands.entity(e).despawn_descendants();
+    let scale = Vec2::new(MINIMAP_W / MAP_W, MINIMAP_H / MAP_H);
+    commands.entity(e).with_children(|parent| {
+        for desc in get_level(room) {
+            if !surface_material(desc.obj_type).solid {
+                continue;
+            }
+            //level positions are the centers of objects, measured from the middle of the room
+            let left = (desc.x_pos - desc.width / 2. + MAP_W / 2.) * scale.x;
+            let bottom = (desc.y_pos - desc.height / 2. + MAP_H / 2.) * scale.y;
+            parent.spawn_bundle(node(
+                left,
+                bottom,
+                (desc.width * scale.x).max(1.),
+                (desc.height * scale.y).max(1.),
+                Color::rgba(0.8, 0.8, 0.8, 0.8),
+            ));
+        }
+    });
+}
+
+pub fn toggle_world_map(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    open: Query<Entity, With<FullMap>>,
+    world_map: Res<WorldMap>,
+    progress: Res<Progress>,
+    manager: Query<&Manager>,
+) {
+    if !keys.just_pressed(KeyCode::M) {
+        return;
+    }
+    if let Some(e) = open.iter().next() {
+        commands.entity(e).despawn_recursive();
+        return;
+    }
+
+    let current = manager.single().room_number;
+    let discovered = |id: i8| progress.discovered.contains(&id);
+    //rooms next to a discovered room show up as unexplored
+    let known = |room: &RoomNode| {
+        discovered(room.id)
+            || world_map
+                .rooms
+                .iter()
+                .any(|r| discovered(r.id) && r.exits.contains(&room.id))
+    };
+    let place = |cell: IVec2| {
+        Vec2::new(
+            WIN_W / 2. + cell.x as f32 * (CELL_W + CELL_GAP) - CELL_W / 2.,
+            WIN_H / 2. + cell.y as f32 * (CELL_H + CELL_GAP) - CELL_H / 2.,
+        )
+    };
+
+    commands
+        .spawn_bundle(node(0., 0., WIN_W, WIN_H, Color::rgba(0., 0., 0., 0.85)))
+        .insert(FullMap)
+        .with_children(|parent| {
+            for room in world_map.rooms.iter().filter(|r| known(r)) {
+                let pos = place(room.cell);
+                //connections between rooms that sit side by side
+                for exit in &room.exits {
+                    if let Some(to) = world_map.room(*exit) {
+                        if to.cell.y == room.cell.y && to.cell.x == room.cell.x + 1 && known(to) {
+                            parent.spawn_bundle(node(pos.x + CELL_W, pos.y + CELL_H / 2. - 2., CELL_GAP, 4., Color::GRAY));
+                        }
+                    }
+                }
+                let color = if room.id == current {
+                    Color::GOLD
+                } else if discovered(room.id) {
+                    Color::SILVER
+                } else {
+                    Color::DARK_GRAY
+                };
+                parent.spawn_bundle(node(pos.x, pos.y, CELL_W, CELL_H, color));
+                if room.id == current {
+                    spawn_dot(parent, pos, Vec2::new(CELL_W, CELL_H));
+                }
+            }
+        });
+}
+
+//plots the player inside whichever room outline each dot belongs to
+fn move_map_dots(player: Query<&Transform, With<Player>>, mut dots: Query<(&MapDot, &mut Style)>) {
+    let pt = player.single();
+    let x = (pt.translation.x / MAP_W + 0.5).clamp(0., 1.);
+    let y = (pt.translation.y / MAP_H + 0.5).clamp(0., 1.);
+    for (dot, mut style) in dots.iter_mut() {
+        style.position.left = Val::Px(dot.origin.x + x * dot.size.x - 3.);
+        style.position.bottom = Val::Px(dot.origin.y + y * dot.size.y - 3.);
+    }
+}
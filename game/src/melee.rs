@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+//one swing in the melee combo
+pub struct ComboStep {
+    pub damage: i32,
+    //hitbox size for a forward swing, turned on its side for up and down swings
+    pub size: Vec2,
+    //how hard it pushes enemies away and up, and how many ticks they're stunned for
+    pub knockback: Vec2,
+    pub stun: u32,
+    //ticks before the next K press counts, then how long after that the combo waits for it
+    pub recovery: u32,
+    pub window: u32,
+}
+
+//K swings through these in order, pressing again inside each step's window moves on to the next
+pub static COMBO: [ComboStep; 3] = [
+    ComboStep {
+        damage: 20,
+        size: Vec2::new(32., 32.),
+        knockback: Vec2::new(3., 2.),
+        stun: 10,
+        recovery: 6,
+        window: 20,
+    },
+    ComboStep {
+        damage: 25,
+        size: Vec2::new(44., 32.),
+        knockback: Vec2::new(5., 3.),
+        stun: 14,
+        recovery: 8,
+        window: 20,
+    },
+    ComboStep {
+        damage: 40,
+        size: Vec2::new(52., 40.),
+        knockback: Vec2::new(9., 5.),
+        stun: 24,
+        recovery: 16,
+        window: 0,
+    },
+];
+
+//how fast a down swing bounces the player up off an enemy or spikes
+pub const POGO_SPEED: f32 = 11.;
+//how much of their speed stunned enemies keep each tick
+pub const STUN_DRAG: f32 = 0.85;
+
+//where the player is in the combo
+pub struct Combo {
+    //the step the next swing uses
+    pub step: usize,
+    recovery: u32,
+    window: u32,
+}
+
+impl Combo {
+    pub fn new() -> Self {
+        Self {
+            step: 0,
+            recovery: 0,
+            window: 0,
+        }
+    }
+    //missing the window drops the combo back to its first step
+    pub fn tick(&mut self) {
+        self.recovery = self.recovery.saturating_sub(1);
+        if self.window > 0 {
+            self.window -= 1;
+            if self.window == 0 {
+                self.step = 0;
+            }
+        }
+    }
+    //the step to swing with, None while the last swing is still recovering
+    pub fn swing(&mut self) -> Option<&'static ComboStep> {
+        if self.recovery > 0 {
+            return None;
+        }
+        let step = &COMBO[self.step];
+        self.recovery = step.recovery;
+        self.window = step.recovery + step.window;
+        self.step = if step.window == 0 { 0 } else { (self.step + 1) % COMBO.len() };
+        Some(step)
+    }
+}
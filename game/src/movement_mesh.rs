@@ -1,6 +1,21 @@
 use bevy::prelude::*;
+use crate::util::*;
+use crate::active_util::*;
 
 pub const MAX_VERT: usize = 75;
+//ticks shaved off the end of a platform's stop so an enemy isn't still stepping on when it leaves
+const BOARD_MARGIN: u32 = 15;
+//highest an enemy can get with a standing jump
+const JUMP_HEIGHT: f32 = 3. * TILE_SIZE;
+//highest an enemy with a double jump can get, jumping again at the top of the first jump
+const DOUBLE_JUMP_HEIGHT: f32 = 5. * TILE_SIZE;
+//highest an enemy can get by jumping at a wall and kicking off it at the top of the jump
+const WALL_JUMP_HEIGHT: f32 = 5. * TILE_SIZE;
+//farthest a wall can be from where the jump starts, and how far back from the wall the kick carries an enemy
+const WALL_REACH: f32 = 3. * TILE_SIZE;
+const WALL_KICK_REACH: f32 = 6. * TILE_SIZE;
+//distance between samples when checking walking edges, also how far up an enemy can step in one sample
+const WALK_STEP: f32 = 4.;
 #[derive(Copy, Clone)]
 pub struct Vertex{
     pub x: f32,
@@ -33,11 +48,35 @@ pub enum Motion{
     JumpLeft,
     Fall,
     Stop,
+    //stand still on a moving platform until it reaches the next vertex
+    Ride,
+    //jump straight up and again at the top, only enemies that can double jump take these edges
+    DoubleJump,
+    //jump up a wall on the other side and kick off it toward the left or right, only for enemies that can wall jump
+    WallJumpLeft,
+    WallJumpRight,
+}
+
+//the ticks within a platform's cycle (see PlatformClock) when an edge can be taken
+#[derive(Copy, Clone)]
+pub struct TimeWindow{
+    pub period: u32,
+    pub open: u32,
+    pub close: u32,
+}
+
+impl TimeWindow{
+    pub fn contains(&self, tick: u32) -> bool{
+        let t = tick % self.period;
+        t >= self.open && t <= self.close
+    }
 }
 
 #[derive(Copy, Clone)]
 pub struct Edge{
     pub path: Motion,
+    //edges onto and off of moving platforms can only be used while the platform is there
+    pub window: Option<TimeWindow>,
 }
 
 impl Edge{
@@ -45,6 +84,13 @@ impl Edge{
     fn new(m: Motion) -> Self{
         Self{
             path: m,
+            window: None,
+        }
+    }
+    fn timed(m: Motion, w: TimeWindow) -> Self{
+        Self{
+            path: m,
+            window: Some(w),
         }
     }
 }
@@ -64,6 +110,265 @@ impl Graph{
     }
 }
 
+//walks the platform through one full cycle the same way move_platforms does
+//returns the waypoint index and the tick the platform arrives for every stop, along with the length of the cycle
+fn platform_schedule(p: &PlatformDescriptor) -> (Vec<(usize, u32)>, u32){
+    let n = p.waypoints.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    match p.mode {
+        PathMode::Loop => {}
+        PathMode::PingPong => {
+            for i in (1..n.saturating_sub(1)).rev() {
+                order.push(i);
+            }
+        }
+    }
+    let mut stops = Vec::new();
+    let mut tick = 0;
+    for (i, w) in order.iter().enumerate() {
+        stops.push((*w, tick));
+        let next = order[(i + 1) % order.len()];
+        let length = p.waypoints[*w].distance(p.waypoints[next]);
+        tick += p.pause + (length / p.speed).ceil() as u32;
+    }
+    return (stops, tick.max(1));
+}
+
+//adds a vertex on top of every waypoint of a moving platform
+//waypoints are joined with Ride edges, and nearby vertices at the same height get edges onto and off of the platform
+//that can only be taken while it is stopped there
+pub fn add_platform_edges(graph: &mut Graph, p: &PlatformDescriptor){
+    let (stops, period) = platform_schedule(p);
+    let existing = graph.vertices.clone();
+    let mut next_id = existing.iter().map(|v| v.id + 1).max().unwrap_or(0);
+    let mut ids = Vec::new();
+    for w in p.waypoints.iter() {
+        if next_id >= MAX_VERT {
+            warn!("Not enough vertices left for moving platform");
+            return;
+        }
+        graph.vertices.push(Vertex::new(w.x, w.y + p.height / 2. + PLAYER_SZ / 2., next_id));
+        ids.push(next_id);
+        next_id += 1;
+    }
+    for (i, (w, arrive)) in stops.iter().enumerate() {
+        let (next, _) = stops[(i + 1) % stops.len()];
+        if next != *w {
+            graph.edges[ids[*w]][ids[next]] = Edge::new(Motion::Ride);
+        }
+        let window = TimeWindow{
+            period: period,
+            open: *arrive,
+            close: (*arrive + p.pause).saturating_sub(BOARD_MARGIN).max(*arrive),
+        };
+        let stop = graph.vertices[existing.len() + *w];
+        for v in existing.iter() {
+            let dx = v.x - stop.x;
+            if (v.y - stop.y).abs() <= 5. && dx.abs() <= p.width / 2. + 2. * TILE_SIZE {
+                if dx > 0. {
+                    graph.edges[v.id][stop.id] = Edge::timed(Motion::Left, window);
+                    graph.edges[stop.id][v.id] = Edge::timed(Motion::Right, window);
+                } else {
+                    graph.edges[v.id][stop.id] = Edge::timed(Motion::Right, window);
+                    graph.edges[stop.id][v.id] = Edge::timed(Motion::Left, window);
+                }
+            }
+        }
+    }
+}
+
+//one way platforms get a vertex at each end and one lined up with the closest vertex underneath them
+//enemies walk between those and off either end onto ground at the same height
+//they can Fall through the platform to the vertex underneath, and Jump back up if it is close enough
+//platforms too high for one jump but low enough for two get a DoubleJump edge instead
+pub fn add_one_way_edges(graph: &mut Graph, level: &[Descriptor]){
+    for desc in level.iter() {
+        if !matches!(desc.obj_type, ObjectType::OneWayPlatform) {
+            continue;
+        }
+        let top = desc.y_pos + desc.height / 2. + PLAYER_SZ / 2.;
+        let mut below: Option<Vertex> = None;
+        for v in graph.vertices.iter() {
+            if (v.x - desc.x_pos).abs() <= desc.width / 2. && v.y < top - 5. {
+                let closer = match below {
+                    Some(b) => top - v.y < top - b.y,
+                    None => true,
+                };
+                if closer {
+                    below = Some(*v);
+                }
+            }
+        }
+        let inset = (desc.width / 2. - PLAYER_SZ / 2.).max(0.);
+        let mut xs = vec![desc.x_pos - inset, desc.x_pos + inset];
+        if let Some(b) = below {
+            xs.push(b.x);
+        }
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        xs.dedup();
+        let existing = graph.vertices.clone();
+        let mut made: Vec<Vertex> = Vec::new();
+        for x in xs {
+            let id = graph.vertices.iter().map(|v| v.id + 1).max().unwrap_or(0);
+            if id >= MAX_VERT {
+                warn!("Not enough vertices left for one way platform");
+                return;
+            }
+            graph.vertices.push(Vertex::new(x, top, id));
+            made.push(Vertex::new(x, top, id));
+            if let Some(b) = below.filter(|b| b.x == x) {
+                graph.edges[id][b.id] = Edge::new(Motion::Fall);
+                if top - b.y <= JUMP_HEIGHT {
+                    graph.edges[b.id][id] = Edge::new(Motion::Jump);
+                } else if top - b.y <= DOUBLE_JUMP_HEIGHT {
+                    graph.edges[b.id][id] = Edge::new(Motion::DoubleJump);
+                }
+            }
+        }
+        //along the platform
+        for pair in made.windows(2) {
+            graph.edges[pair[0].id][pair[1].id] = Edge::new(Motion::Right);
+            graph.edges[pair[1].id][pair[0].id] = Edge::new(Motion::Left);
+        }
+        //off each end to the closest vertex that can be walked to without a drop
+        let ends = [(made[0], -1.), (made[made.len() - 1], 1.)];
+        for (e, dir) in ends {
+            let next = existing
+                .iter()
+                .filter(|v| (v.x - e.x) * dir > 0. && level_walk(&e, v, level))
+                .min_by(|a, b| (a.x - e.x).abs().partial_cmp(&(b.x - e.x).abs()).unwrap());
+            if let Some(v) = next {
+                let (out, back) = if dir > 0. { (Motion::Right, Motion::Left) } else { (Motion::Left, Motion::Right) };
+                graph.edges[e.id][v.id] = Edge::new(out);
+                graph.edges[v.id][e.id] = Edge::new(back);
+            }
+        }
+    }
+}
+
+//vertices too high to jump to, with a wall just past the vertex under them to kick off, get a wall jump edge
+//WallJumpLeft runs right at a wall and kicks back left onto the ledge, WallJumpRight is the mirror image
+pub fn add_wall_jump_edges(graph: &mut Graph, level: &[Descriptor]){
+    let vertices = graph.vertices.clone();
+    for a in vertices.iter() {
+        for b in vertices.iter() {
+            let rise = b.y - a.y;
+            if rise <= JUMP_HEIGHT || rise > WALL_JUMP_HEIGHT || !matches!(graph.edges[a.id][b.id].path, Motion::Stop) {
+                continue;
+            }
+            for desc in level.iter() {
+                if !surface_material(desc.obj_type).solid
+                    || slope_ratio(desc.obj_type).is_some()
+                    || matches!(desc.obj_type, ObjectType::OneWayPlatform) {
+                    continue;
+                }
+                //the wall has to run from the enemy's feet up to the ledge
+                let bottom = desc.y_pos - desc.height / 2.;
+                let top = desc.y_pos + desc.height / 2.;
+                if bottom > a.y || top < b.y - PLAYER_SZ / 2. {
+                    continue;
+                }
+                //a wall right of `a` is kicked off toward the left, one left of it toward the right
+                for (dir, motion) in [(1., Motion::WallJumpLeft), (-1., Motion::WallJumpRight)] {
+                    let face = desc.x_pos - dir * desc.width / 2.;
+                    let to_wall = (face - a.x) * dir;
+                    let kick = (face - b.x) * dir;
+                    if to_wall > 0. && to_wall <= WALL_REACH && kick >= PLAYER_SZ && kick <= WALL_KICK_REACH {
+                        graph.edges[a.id][b.id] = Edge::new(motion);
+                    }
+                }
+            }
+        }
+    }
+}
+
+//walking from a to b on ground at one height the whole way, without a drop, a climb or a wall
+fn level_walk(a: &Vertex, b: &Vertex, level: &[Descriptor]) -> bool{
+    if (a.y - b.y).abs() > 1. {
+        return false;
+    }
+    let feet = a.y - PLAYER_SZ / 2.;
+    let dir = if b.x > a.x { 1. } else { -1. };
+    let mut x = a.x;
+    while (b.x - x) * dir > 0. {
+        x += dir * WALK_STEP;
+        let ground = level.iter().any(|desc| ground_height(desc, x).map_or(false, |s| (s - feet).abs() <= 1.));
+        if !ground {
+            return false;
+        }
+    }
+    return walkable(a, b, level);
+}
+
+//top of the terrain in desc under a body centred at x, slopes give the height of their surface there
+//a box holds the body up until all of it has gone past the edge, the same as the collisions
+fn ground_height(desc: &Descriptor, x: f32) -> Option<f32>{
+    if slope_ratio(desc.obj_type).is_some() {
+        return slope_surface(desc.obj_type, desc.width, desc.height, Vec3::new(desc.x_pos, desc.y_pos, 0.), x, PLAYER_SZ / 2.);
+    }
+    if (x - desc.x_pos).abs() >= (desc.width + PLAYER_SZ) / 2. {
+        return None;
+    }
+    if surface_material(desc.obj_type).solid {
+        return Some(desc.y_pos + desc.height / 2.);
+    }
+    return None;
+}
+
+//walks from a to b the way an enemy would, following the ground up and down slopes
+//walking off a ledge falls under gravity while still moving sideways at walking speed
+//returns false if something solid is in the way at body height
+fn walkable(a: &Vertex, b: &Vertex, level: &[Descriptor]) -> bool{
+    let dir = if b.x > a.x { 1. } else { -1. };
+    //ticks it takes to walk one sample
+    let dt = WALK_STEP / PLAYER_SPEED;
+    let mut feet = a.y - PLAYER_SZ / 2.;
+    let mut fall = 0.;
+    let mut x = a.x;
+    while (b.x - x) * dir > 0. {
+        x += dir * WALK_STEP;
+        let mut ground = f32::MIN;
+        for desc in level.iter() {
+            let surface = match ground_height(desc, x) {
+                Some(s) => s,
+                None => continue,
+            };
+            if surface <= feet + WALK_STEP + 1. {
+                ground = ground.max(surface);
+            } else if !matches!(desc.obj_type, ObjectType::OneWayPlatform)
+                && desc.y_pos - desc.height / 2. < feet + PLAYER_SZ {
+                return false;
+            }
+        }
+        //stepping up or down a slope keeps the feet on it, anything further down is fallen to
+        if ground >= feet - WALK_STEP - 1. {
+            feet = ground;
+            fall = 0.;
+        } else {
+            fall += GRAVITY * dt;
+            feet = (feet + fall * dt).max(ground);
+        }
+    }
+    return true;
+}
+
+//checks every Left and Right edge against the room, so hand written meshes can't walk enemies into walls
+pub fn check_walk_edges(graph: &Graph, level: &[Descriptor]) -> Vec<(usize, usize)>{
+    let mut bad = Vec::new();
+    for a in graph.vertices.iter() {
+        for b in graph.vertices.iter() {
+            let edge = graph.edges[a.id][b.id];
+            if edge.window.is_some() || !matches!(edge.path, Motion::Left | Motion::Right) {
+                continue;
+            }
+            if !walkable(a, b, level) {
+                bad.push((a.id, b.id));
+            }
+        }
+    }
+    return bad;
+}
+
 pub fn get_level_mesh(id: i8) -> Graph{
 
     let mut graph = Graph::new();
@@ -155,7 +460,8 @@ pub fn get_level_mesh(id: i8) -> Graph{
         graph.edges[22][20] = Edge::new(Motion::Right);
         graph.edges[16][22] = Edge::new(Motion::Left);
         graph.edges[22][16] = Edge::new(Motion::JumpRight);
-        graph.vertices.push(Vertex::new_scaled(16.5, 3.5, 24));
+        //clear of the foot of the hump's shallow slope
+        graph.vertices.push(Vertex::new_scaled(17., 3.5, 24));
         graph.edges[22][24] = Edge::new(Motion::Left);
         graph.edges[24][22] = Edge::new(Motion::Right);
         graph.edges[16][24] = Edge::new(Motion::Left);
@@ -1,13 +1,45 @@
 use crate::active_util::*;
 use crate::ai::*;
+use crate::collisions::*;
+use crate::difficulty::*;
+use crate::enemies::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::player::*;
+use crate::status_effects::*;
 use crate::util::*;
+use crate::weapons::*;
 use bevy::asset;
 use bevy::prelude::*;
 use bevy::sprite::collide_aabb::Collision;
+use iyes_loopless::prelude::*;
 use rand::Rng;
 
 const PROJECTILE_SZ: f32 = 6.;
-const PROJECTILE_DAMAGE: i32 = 25;
+
+//gravity, collisions against the level, moving platforms, projectiles and breaking things
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_gameplay_system(gravity_on_movables.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::GravityOnMovables).after(GameplaySystem::MoveEnemies))
+            //platforms move and carry whatever is standing on them
+            .add_gameplay_system(move_platforms.run_in_state(GameState::Playing).label(Step::Platforms).label(GameplaySystem::MovePlatforms).after(Step::Movement))
+            //projected positions get resolved against the level
+            .add_gameplay_system(barrels_with_barrels.run_in_state(GameState::Playing).label(Step::Collisions).label(GameplaySystem::BarrelsWithBarrels).after(GameplaySystem::ChangePlayerDirection).after(Step::Movement))
+            .add_gameplay_system(apply_collisions.run_in_state(GameState::Playing).label(Step::Collisions).label(GameplaySystem::ApplyCollisions).after(GameplaySystem::BarrelsWithBarrels).after(Step::Movement))
+            .add_gameplay_system(enemy_collisions.run_in_state(GameState::Playing).label(Step::Collisions).label(GameplaySystem::EnemyCollisions).after(GameplaySystem::ApplyCollisions).after(Step::Movement))
+            .add_gameplay_system(object_collisions.run_in_state(GameState::Playing).label(Step::Collisions).label(GameplaySystem::ObjectCollisions).after(GameplaySystem::EnemyCollisions).after(Step::Movement))
+            .add_gameplay_system(update_positions.run_in_state(GameState::Playing).label(Step::Positions).label(GameplaySystem::UpdatePositions).after(Step::Collisions))
+            //hits, projectiles and everything that breaks because of them
+            .add_gameplay_system(projectile_active_collision.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::ProjectileActiveCollision).after(GameplaySystem::MeleeboxCollisions).after(Step::Movement))
+            .add_gameplay_system(projectile_static_collisions.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::ProjectileStaticCollisions).after(GameplaySystem::ProjectileActiveCollision).after(Step::Movement))
+            .add_gameplay_system(break_objects.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::BreakObjects).after(GameplaySystem::RewardKills).after(Step::Movement))
+            .add_gameplay_system(break_hb_objects.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::BreakHbObjects).after(GameplaySystem::BreakObjects).after(Step::Movement))
+            .add_gameplay_system(despawn_broken_objects.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::DespawnBrokenObjects).after(GameplaySystem::BreakHbObjects).after(Step::Movement));
+    }
+}
 
 #[derive(Component)]
 pub struct MovableObject;
@@ -24,6 +56,14 @@ pub struct Projectile {
     pub velocity: Vec2,
     pub proj_type: ProjType,
     pub project_pos: Vec3,
+    //the rest comes from the weapon that fired it, see weapons.rs
+    pub damage: f32,
+    pub gravity_scale: f32,
+    pub pierce: u32,
+    pub bounces: u32,
+    pub fuse: Option<u32>,
+    //enemies already hurt by it, so going through one only hurts it once
+    pub hit: Vec<Entity>,
 }
 
 impl Projectile {
@@ -32,8 +72,61 @@ impl Projectile {
             velocity: vel,
             proj_type: pt,
             project_pos: Vec3::splat(0.),
+            damage: 1.,
+            gravity_scale: 1.,
+            pierce: 0,
+            bounces: 0,
+            fuse: None,
+            hit: Vec::new(),
+        }
+    }
+    pub fn from_weapon(vel: Vec2, weapon: &Weapon) -> Self {
+        Self {
+            damage: weapon.damage,
+            gravity_scale: weapon.gravity_scale,
+            pierce: weapon.pierce,
+            bounces: weapon.bounces,
+            fuse: weapon.fuse,
+            ..Projectile::new(vel, ProjType::Projectile)
         }
     }
+    //whether it keeps going after touching `o`, which it does through the first `pierce` enemies it hurt
+    fn passes_through(&self, o: &Object, e: Entity) -> bool {
+        is_enemy(o.obj_type)
+            && self.hit.iter().position(|h| *h == e).map_or(false, |i| i < self.pierce as usize)
+    }
+    //whether it touches `o` this tick, both projectile collision systems use this box so they agree on what a shot hit
+    fn touching(&self, o: &Object, t: &Transform) -> Option<Collision> {
+        bevy::sprite::collide_aabb::collide(
+            self.project_pos,
+            Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ),
+            t.translation,
+            Vec2::new(o.width, o.height),
+        )
+    }
+}
+
+//one piece of an explosion, barrels and grenades throw these
+pub fn spawn_particle(commands: &mut Commands, at: Vec3, velocity: Vec2, size: f32) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::RED,
+                custom_size: Some(Vec2::new(size, size)),
+                ..default()
+            },
+            transform: Transform {
+                translation: Vec3::new(at.x, at.y, 2.),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Projectile::new(velocity, ProjType::Particle))
+        .insert(BrokenObj::new(Timer::from_seconds(4.0, false)));
+}
+
+fn is_enemy(t: ObjectType) -> bool {
+    matches!(t, ObjectType::MeleeEnemy | ObjectType::RangedEnemy | ObjectType::OtherEnemy)
 }
 
 #[derive(Component)]
@@ -47,37 +140,150 @@ impl BrokenObj {
     }
 }
 
+//ticks since the current room was loaded, moving platforms and the movement mesh use it to agree on where platforms are
+pub struct PlatformClock {
+    pub ticks: u32,
+}
+
+impl PlatformClock {
+    pub fn new() -> Self {
+        Self { ticks: 0 }
+    }
+}
+
+#[derive(Component)]
+pub struct MovingPlatform {
+    pub waypoints: Vec<Vec2>,
+    pub speed: f32,
+    pub pause: u32,
+    pub mode: PathMode,
+    pub next: usize,
+    pub reverse: bool,
+    pub wait: u32,
+    pub velocity: Vec2,
+}
+
+impl MovingPlatform {
+    pub fn new(desc: &PlatformDescriptor) -> Self {
+        Self {
+            waypoints: desc.waypoints.clone(),
+            speed: desc.speed,
+            pause: desc.pause,
+            mode: desc.mode,
+            next: 1 % desc.waypoints.len(),
+            reverse: false,
+            //platforms start out waiting at their first waypoint
+            wait: desc.pause,
+            velocity: Vec2::splat(0.),
+        }
+    }
+
+    fn advance(&mut self) {
+        let n = self.waypoints.len();
+        match self.mode {
+            PathMode::Loop => {
+                self.next = (self.next + 1) % n;
+            }
+            PathMode::PingPong => {
+                if !self.reverse && self.next + 1 >= n {
+                    self.reverse = true;
+                } else if self.reverse && self.next == 0 {
+                    self.reverse = false;
+                }
+                if self.reverse {
+                    self.next = self.next.saturating_sub(1);
+                } else {
+                    self.next = (self.next + 1) % n;
+                }
+            }
+        }
+    }
+}
+
+//moves platforms along their waypoints and carries anything standing on them
+//runs after the projected positions are calculated so apply_collisions can snap riders onto the moved platform
+pub fn move_platforms(
+    mut clock: ResMut<PlatformClock>,
+    mut platforms: Query<(&Object, &mut MovingPlatform, &mut Transform), Without<ActiveObject>>,
+    mut actives: Query<(&Object, &mut ActiveObject, &Transform), With<ActiveObject>>,
+) {
+    for (o, mut mp, mut t) in platforms.iter_mut() {
+        let old = t.translation;
+        if mp.wait > 0 {
+            mp.wait -= 1;
+        } else if mp.waypoints.len() > 1 {
+            let target = mp.waypoints[mp.next];
+            let to_target = target - Vec2::new(old.x, old.y);
+            if to_target.length() <= mp.speed {
+                t.translation.x = target.x;
+                t.translation.y = target.y;
+                mp.wait = mp.pause;
+                mp.advance();
+            } else {
+                let step = to_target.normalize() * mp.speed;
+                t.translation.x += step.x;
+                t.translation.y += step.y;
+            }
+        }
+        mp.velocity = Vec2::new(t.translation.x - old.x, t.translation.y - old.y);
+        if mp.velocity == Vec2::ZERO {
+            continue;
+        }
+
+        //anything whose feet were on the platform before it moved gets moved with it
+        let top = old.y + o.height / 2.;
+        for (ao, mut active, at) in actives.iter_mut() {
+            let feet = at.translation.y - ao.height / 2.;
+            let overlap = (at.translation.x - old.x).abs() < (o.width + ao.width) / 2.;
+            if overlap && (feet - top).abs() <= 1. && active.velocity.y <= 0. {
+                active.projected_position.x += mp.velocity.x;
+                active.projected_position.y += mp.velocity.y;
+            }
+        }
+    }
+    clock.ticks += 1;
+}
+
+//L fires the weapon that's out, Q switches to the next one
 pub fn shoot(
-    input: Res<Input<KeyCode>>,
+    input: Res<TickInput>,
     mut commands: Commands,
     mut player: Query<(&mut Player, &mut ActiveObject, &mut Transform), (With<Player>)>,
     asset_server: Res<AssetServer>,
 ) {
     let (mut p, pl, pt) = player.single_mut();
+    let arsenal = &mut p.arsenal;
+    arsenal.cooldown = arsenal.cooldown.saturating_sub(1);
+    if input.just_pressed(KeyCode::Q) {
+        arsenal.next_weapon();
+        println!("{} out, {} shots left", arsenal.current().name, arsenal.ammo_left());
+    }
 
-    let mut vel = Vec2::new(15., 4.);
+    let mut aim = Vec2::new(15., 4.);
 
     if pl.facing_left {
-        vel.x *= -1.;
+        aim.x *= -1.;
     }
     if input.pressed(KeyCode::W) {
-        vel.x = 0.;
-        vel.y = 17.;
+        aim = Vec2::Y;
     } else if input.pressed(KeyCode::S) {
-        vel.x = 0.;
-        vel.y = -15.;
+        aim = -Vec2::Y;
     }
-    vel += pl.velocity;
 
-    if input.just_pressed(KeyCode::L) {
-        // for (pla)
-        if p.credits > 0 {
-            p.credits -= 5;
+    if input.pressed(KeyCode::L) {
+        if !arsenal.fire() {
+            if arsenal.cooldown == 0 && input.just_pressed(KeyCode::L) {
+                println!("Out of ammo for the {}!", arsenal.current().name);
+            }
+            return;
+        }
+        let weapon = arsenal.current();
+        for vel in shot_velocities(weapon, aim) {
             commands
                 .spawn_bundle(SpriteBundle {
                     sprite: Sprite {
-                        color: Color::YELLOW,
-                        custom_size: Some(Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ)),
+                        color: weapon.color,
+                        custom_size: Some(Vec2::new(weapon.size, weapon.size)),
                         ..default()
                     },
                     transform: Transform {
@@ -87,7 +293,7 @@ pub fn shoot(
                     // texture: asset_server.load("bullet.png"),
                     ..default()
                 })
-                .insert(Projectile::new(vel, ProjType::Projectile));
+                .insert(Projectile::from_weapon(vel + pl.velocity, weapon));
         }
     }
 }
@@ -105,22 +311,50 @@ pub fn projectile_static_collisions(
 ) {
     //let (pl, pt) = player.single_mut();
     for (mut pro_o, mut pro_t, entity) in projectiles.iter_mut() {
-        pro_o.velocity.y += GRAVITY;
+        pro_o.velocity.y += GRAVITY * pro_o.gravity_scale;
         let mut collide = false;
+        //where a grenade goes off this tick, when its fuse runs out or it stops against something
+        let mut blast: Option<Vec3> = None;
+        if let Some(fuse) = pro_o.fuse {
+            if fuse == 0 {
+                blast = Some(pro_t.translation);
+            } else {
+                pro_o.fuse = Some(fuse - 1);
+            }
+        }
 
         for (mut o_o, o_t, o_e) in objects.iter_mut() {
-            let res = bevy::sprite::collide_aabb::collide(
-                pro_o.project_pos,
-                Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ),
-                o_t.translation,
-                Vec2::new(o_o.width, o_o.height),
-            );
+            let res = pro_o.touching(&o_o, o_t);
             if res.is_some() {
+                //piercing shots carry on through enemies as if they weren't there
+                if matches!(pro_o.proj_type, ProjType::Projectile) && pro_o.passes_through(&o_o, o_e) {
+                    continue;
+                }
                 collide = true;
                 let coll_type: bevy::sprite::collide_aabb::Collision = res.unwrap();
                 let mut time: f32 = 5.0;
                 if matches!(pro_o.proj_type, ProjType::Projectile) {
-                    commands.entity(entity).despawn();
+                    if pro_o.bounces > 0 && surface_material(o_o.obj_type).solid {
+                        pro_o.bounces -= 1;
+                        match coll_type {
+                            Collision::Left | Collision::Right => {
+                                pro_o.velocity.x *= -0.8;
+                            }
+                            Collision::Top | Collision::Bottom => {
+                                pro_o.velocity.y *= -0.6;
+                                pro_o.velocity.x *= 0.8;
+                            }
+                            Collision::Inside => {
+                                pro_o.velocity *= -0.5;
+                            }
+                        }
+                        continue;
+                    }
+                    if pro_o.fuse.is_some() {
+                        blast = Some(pro_o.project_pos);
+                    } else {
+                        commands.entity(entity).despawn();
+                    }
                     if matches!(o_o.obj_type, ObjectType::Breakable) {
                         // generate_breakables(&coll_type, o_t, o_o, commands);
                         o_o.broken = true;
@@ -235,16 +469,27 @@ pub fn projectile_static_collisions(
         if !collide {
             pro_t.translation = pro_o.project_pos;
         }
+        //grenades blow up like barrels, throwing shrapnel and breaking anything breakable close by
+        if let Some(at) = blast {
+            commands.entity(entity).despawn();
+            spawn_shrapnel(&mut commands, at);
+            for (mut o_o, o_t, _) in objects.iter_mut() {
+                if matches!(o_o.obj_type, ObjectType::Barrel | ObjectType::Breakable)
+                    && o_t.translation.truncate().distance(at.truncate()) <= BLAST_RADIUS
+                {
+                    o_o.broken = true;
+                }
+            }
+        }
     }
 }
 
 pub fn despawn_broken_objects(
-    time: Res<Time>,
     mut commands: Commands,
     mut brokenObjects: Query<(&mut BrokenObj, Entity), Without<Object>>,
 ) {
     for (mut obj, entity) in brokenObjects.iter_mut() {
-        obj.lifespan.tick(time.delta());
+        obj.lifespan.tick(TICK);
         if obj.lifespan.finished() {
             commands.entity(entity).despawn();
         }
@@ -263,30 +508,36 @@ pub fn projectile_active_collision(
         ),
     >,
     mut actives: Query<
-        (&mut ActiveObject, Entity, &mut Object),
+        (&ActiveObject, &Transform, Entity, &mut Object, Option<&mut StatusEffects>),
         (With<ActiveObject>, Without<Player>, Without<Projectile>),
     >,
-    mut player: Query<(&mut Player, &ActiveObject), With<Player>>,
+    mut player: Query<(&Player, &ActiveObject, &mut StatusEffects, Entity), With<Player>>,
+    difficulty: Res<Difficulty>,
+    mut damage: EventWriter<DamageEvent>,
 ) {
+    let projectile_damage = difficulty.preset().projectile_damage;
     for (mut pro_o, mut pro_t, entity_p) in projectiles.iter_mut() {
         pro_o.project_pos = Vec3::new(
             pro_t.translation.x + pro_o.velocity.x,
             pro_t.translation.y + pro_o.velocity.y,
             0.,
         );
-        for (mut e_o, entity, o_o) in actives.iter_mut() {
-            let res = bevy::sprite::collide_aabb::collide(
-                pro_o.project_pos,
-                Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ),
-                e_o.projected_position,
-                Vec2::new(PLAYER_SZ, PLAYER_SZ),
-            );
+        for (_, e_t, entity, o_o, mut effects) in actives.iter_mut() {
+            let res = pro_o.touching(&o_o, e_t);
             if res.is_some() {
                 let coll_type: bevy::sprite::collide_aabb::Collision = res.unwrap();
                 if matches!(pro_o.proj_type, ProjType::Particle) {
                     // let mut p = player.single_mut();
-                    e_o.health -= 25;
-                    // print!("{}\n", e_o.health);
+                    damage.send(DamageEvent {
+                        target: entity,
+                        amount: 25,
+                        source: Some(entity_p),
+                        kind: DamageKind::Explosion,
+                    });
+                    //explosions set whatever they hit on fire
+                    if let Some(effects) = effects.as_mut() {
+                        effects.add(Effect::Burn);
+                    }
                     commands.entity(entity_p).despawn();
                     match coll_type {
                         Collision::Top => {
@@ -310,47 +561,79 @@ pub fn projectile_active_collision(
                         }
                     }
                 } else if matches!(pro_o.proj_type, ProjType::Projectile) {
-                    e_o.health -= PROJECTILE_DAMAGE;
+                    if !pro_o.hit.contains(&entity) {
+                        pro_o.hit.push(entity);
+                        damage.send(DamageEvent {
+                            target: entity,
+                            amount: (projectile_damage as f32 * pro_o.damage).round() as i32,
+                            source: Some(entity_p),
+                            kind: DamageKind::Projectile,
+                        });
+                    }
                 } else if matches!(pro_o.proj_type, ProjType::BrokenObj) {
                     if (pro_o.velocity.y <= -5.) {
-                        e_o.health -= 20;
+                        damage.send(DamageEvent {
+                            target: entity,
+                            amount: 20,
+                            source: Some(entity_p),
+                            kind: DamageKind::Debris,
+                        });
                     }
                 }
             }
-            let (mut p, po) = player.single_mut();
-            let res2 = bevy::sprite::collide_aabb::collide(
-                pro_o.project_pos,
-                Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ),
-                po.projected_position,
-                Vec2::new(PLAYER_SZ, PLAYER_SZ),
-            );
-            if res2.is_some() {
-                // let coll_type: bevy::sprite::collide_aabb::Collision = res2.unwrap();
-                if matches!(pro_o.proj_type, ProjType::Particle) {
-                    // if (pro_o.velocity.x * pro_o.velocity.y).round() as i8 > 30 {
-                    //     p.health -= 30;
-                    // } else if (pro_o.velocity.x * pro_o.velocity.y).round() as i8 > 3 {
-                    //     p.health -= (pro_o.velocity.x * pro_o.velocity.x).round() as i8;
-                    // }
-                    let mag = pro_o.velocity.x.powi(2) + pro_o.velocity.y.powi(2);
-                    if mag >= 1. {
-                        p.health -= 1;
-                    }
-                    commands.entity(entity_p).despawn();
-                } else if matches!(pro_o.proj_type, ProjType::BrokenObj) {
-                    // if (pro_o.velocity.y).round() as i8 > 10 {
-                    if (pro_o.velocity.y <= -5.) {
-                        p.health -= 5;
-                    }
-                    // } else if (pro_o.velocity.x * pro_o.velocity.y).round() as i8 > 3 {
-                    //     p.health -= (pro_o.velocity.x * pro_o.velocity.x).round() as i8;
-                    // }
-                    commands.entity(entity_p).despawn();
-                    print!("Ouch\n");
-                } else if matches!(pro_o.proj_type, ProjType::EnemyProjectile) {
-                    p.health -= 1;
-                    commands.entity(entity_p).despawn();
+        }
+        let (p, po, mut player_effects, pe) = player.single_mut();
+        let res2 = bevy::sprite::collide_aabb::collide(
+            pro_o.project_pos,
+            Vec2::new(PROJECTILE_SZ, PROJECTILE_SZ),
+            po.projected_position,
+            Vec2::new(PLAYER_SZ, PLAYER_SZ),
+        );
+        //projectiles pass through a dashing player
+        if res2.is_some() && !p.dash.invulnerable() {
+            // let coll_type: bevy::sprite::collide_aabb::Collision = res2.unwrap();
+            if matches!(pro_o.proj_type, ProjType::Particle) {
+                // if (pro_o.velocity.x * pro_o.velocity.y).round() as i8 > 30 {
+                //     p.health -= 30;
+                // } else if (pro_o.velocity.x * pro_o.velocity.y).round() as i8 > 3 {
+                //     p.health -= (pro_o.velocity.x * pro_o.velocity.x).round() as i8;
+                // }
+                let mag = pro_o.velocity.x.powi(2) + pro_o.velocity.y.powi(2);
+                if mag >= 1. {
+                    damage.send(DamageEvent {
+                        target: pe,
+                        amount: 1,
+                        source: Some(entity_p),
+                        kind: DamageKind::Explosion,
+                    });
+                    player_effects.add(Effect::Burn);
                 }
+                commands.entity(entity_p).despawn();
+            } else if matches!(pro_o.proj_type, ProjType::BrokenObj) {
+                // if (pro_o.velocity.y).round() as i8 > 10 {
+                if (pro_o.velocity.y <= -5.) {
+                    damage.send(DamageEvent {
+                        target: pe,
+                        amount: 5,
+                        source: Some(entity_p),
+                        kind: DamageKind::Debris,
+                    });
+                }
+                // } else if (pro_o.velocity.x * pro_o.velocity.y).round() as i8 > 3 {
+                //     p.health -= (pro_o.velocity.x * pro_o.velocity.x).round() as i8;
+                // }
+                commands.entity(entity_p).despawn();
+                print!("Ouch\n");
+            } else if matches!(pro_o.proj_type, ProjType::EnemyProjectile) {
+                //ranged enemies' shots are poisoned
+                damage.send(DamageEvent {
+                    target: pe,
+                    amount: 1,
+                    source: Some(entity_p),
+                    kind: DamageKind::Projectile,
+                });
+                player_effects.add(Effect::Poison);
+                commands.entity(entity_p).despawn();
             }
         }
     }
@@ -358,6 +641,7 @@ pub fn projectile_active_collision(
 
 pub fn break_objects(
     mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
     mut objects: Query<
         (&mut Object, &Transform, Entity),
         (With<Object>, Without<Player>, Without<Projectile>),
@@ -369,7 +653,7 @@ pub fn break_objects(
 ) {
     for (mut pro_o, mut pro_t, entity) in projectiles.iter_mut() {
         let mut collide = false;
-        pro_o.velocity.y += GRAVITY;
+        pro_o.velocity.y += GRAVITY * pro_o.gravity_scale;
         for (mut o_o, o_t, o_e) in objects.iter_mut() {
             let res = bevy::sprite::collide_aabb::collide(
                 pro_o.project_pos,
@@ -380,15 +664,14 @@ pub fn break_objects(
             if res.is_some() {
                 let coll_type: bevy::sprite::collide_aabb::Collision = res.unwrap();
                 let mut time: f32 = 5.0;
+                //projectile_static_collisions already decided whether the shot stops here
                 if matches!(pro_o.proj_type, ProjType::Projectile) {
-                    commands.entity(entity).despawn();
                     if matches!(o_o.obj_type, ObjectType::Breakable) {
                         if o_o.broken {
                             commands.entity(o_e).despawn();
 
-                            let mut rng = rand::thread_rng();
+                            let rng = &mut game_rng.rng;
                             for i in 1..5 {
-                                let mut rng = rand::thread_rng();
                                 let mut p_xvel = 0.;
                                 let mut p_yvel = 0.;
                                 match coll_type {
@@ -462,9 +745,8 @@ pub fn break_objects(
                         if (o_o.broken) {
                             commands.entity(o_e).despawn();
 
-                            let mut rng = rand::thread_rng();
+                            let rng = &mut game_rng.rng;
                             for i in 1..10 {
-                                let mut rng = rand::thread_rng();
                                 let mut p_xvel = 0.;
                                 let mut p_yvel = 0.;
                                 match coll_type {
@@ -509,29 +791,7 @@ pub fn break_objects(
                                     }
                                 }
                                 let sz = o_o.height / rng.gen_range(8, 16) as f32;
-                                commands
-                                    .spawn_bundle(SpriteBundle {
-                                        sprite: Sprite {
-                                            color: Color::RED,
-                                            custom_size: Some(Vec2::new(sz, sz)),
-                                            ..default()
-                                        },
-                                        transform: Transform {
-                                            translation: Vec3::new(
-                                                o_t.translation.x,
-                                                o_t.translation.y,
-                                                2.,
-                                            ),
-                                            ..default()
-                                        },
-                                        // texture: asset_server.load("bullet.png"),
-                                        ..default()
-                                    })
-                                    .insert(Projectile::new(
-                                        Vec2::new(p_xvel, p_yvel as f32),
-                                        ProjType::Particle,
-                                    ))
-                                    .insert(BrokenObj::new(Timer::from_seconds(4.0, false)));
+                                spawn_particle(&mut commands, o_t.translation, Vec2::new(p_xvel, p_yvel), sz);
                             }
                         }
                     }
@@ -540,9 +800,8 @@ pub fn break_objects(
                         if o_o.broken {
                             commands.entity(o_e).despawn();
 
-                            let mut rng = rand::thread_rng();
+                            let rng = &mut game_rng.rng;
                             for i in 1..10 {
-                                let mut rng = rand::thread_rng();
                                 let mut p_xvel = 0.;
                                 let mut p_yvel = 0.;
                                 match coll_type {
@@ -587,29 +846,7 @@ pub fn break_objects(
                                     }
                                 }
                                 let sz = o_o.height / rng.gen_range(8, 16) as f32;
-                                commands
-                                    .spawn_bundle(SpriteBundle {
-                                        sprite: Sprite {
-                                            color: Color::RED,
-                                            custom_size: Some(Vec2::new(sz, sz)),
-                                            ..default()
-                                        },
-                                        transform: Transform {
-                                            translation: Vec3::new(
-                                                o_t.translation.x,
-                                                o_t.translation.y,
-                                                2.,
-                                            ),
-                                            ..default()
-                                        },
-                                        // texture: asset_server.load("bullet.png"),
-                                        ..default()
-                                    })
-                                    .insert(Projectile::new(
-                                        Vec2::new(p_xvel, p_yvel as f32),
-                                        ProjType::Particle,
-                                    ))
-                                    .insert(BrokenObj::new(Timer::from_seconds(4.0, false)));
+                                spawn_particle(&mut commands, o_t.translation, Vec2::new(p_xvel, p_yvel), sz);
                             }
                         }
                     }
@@ -622,6 +859,7 @@ pub fn break_objects(
 pub fn break_hb_objects(
     mut player: Query<(&mut Player, &Transform), With<Player>>,
     mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
     mut objects: Query<
         (&mut Object, &Transform, Entity),
         (With<Object>, Without<Player>, Without<Projectile>),
@@ -637,7 +875,7 @@ pub fn break_hb_objects(
             } else {
                 false
             };
-            let mut rng = rand::thread_rng();
+            let rng = &mut game_rng.rng;
             let mut p_xvel = 0.;
             let mut p_yvel = 0.;
             commands.entity(o_e).despawn();
@@ -658,29 +896,10 @@ pub fn break_hb_objects(
                         p_xvel = (i as f32 - 5.) / 2. + 2.;
                     }
                     let sz = o_o.height / rng.gen_range(8, 16) as f32;
-                    commands
-                        .spawn_bundle(SpriteBundle {
-                            sprite: Sprite {
-                                color: Color::RED,
-                                custom_size: Some(Vec2::new(sz, sz)),
-                                ..default()
-                            },
-                            transform: Transform {
-                                translation: Vec3::new(o_t.translation.x, o_t.translation.y, 2.),
-                                ..default()
-                            },
-                            // texture: asset_server.load("bullet.png"),
-                            ..default()
-                        })
-                        .insert(Projectile::new(
-                            Vec2::new(p_xvel, p_yvel as f32),
-                            ProjType::Particle,
-                        ))
-                        .insert(BrokenObj::new(Timer::from_seconds(4.0, false)));
+                    spawn_particle(&mut commands, o_t.translation, Vec2::new(p_xvel, p_yvel), sz);
                 }
             } else if matches!(o_o.obj_type, ObjectType::Breakable) {
                 for i in 1..5 {
-                    let mut rng = rand::thread_rng();
                     let mut p_xvel = 0.;
                     let mut p_yvel = 0.;
                     if (horizontal && pt.translation.x < o_t.translation.x) {
@@ -0,0 +1,271 @@
+use crate::abilities::*;
+use crate::ai::Enemy;
+use crate::active_util::*;
+use crate::collisions::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::items::grappling_hook::*;
+use crate::items::*;
+use crate::melee::*;
+use crate::physics::*;
+use crate::status_effects::*;
+use crate::util::*;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+//the player's movement, melee and shooting
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_gameplay_system(move_player.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::MovePlayer).after(Step::Input))
+            .add_gameplay_system(swing_on_rope.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::SwingOnRope).after(GameplaySystem::MovePlayer))
+            .add_gameplay_system(attack.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::Attack).after(GameplaySystem::GravityOnMovables))
+            .add_gameplay_system(shoot.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::Shoot).after(GameplaySystem::Attack))
+            .add_gameplay_system(change_player_direction.run_in_state(GameState::Playing).label(Step::Collisions).label(GameplaySystem::ChangePlayerDirection).after(Step::Platforms).after(Step::Movement))
+            .add_gameplay_system(attack_static.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::AttackStatic).after(Step::Positions).after(Step::Movement))
+            .add_gameplay_system(attack_active.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::AttackActive).after(GameplaySystem::AttackStatic).after(Step::Movement))
+            .add_gameplay_system(player_health.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::PlayerHealth).after(GameplaySystem::DespawnBrokenObjects).after(Step::Movement))
+            .add_gameplay_system(collect_ability_pickups.run_in_state(GameState::Playing).label(Step::Level).label(GameplaySystem::CollectAbilityPickups).after(Step::Sight))
+            .add_system(draw_dash_trail.run_in_state(GameState::Playing))
+            .add_system(draw_rope.run_in_state(GameState::Playing));
+    }
+}
+
+pub fn move_player(
+    input: Res<TickInput>,
+    mut player: Query<(&mut ActiveObject, &Transform, &mut Player, &mut Health, &StatusEffects), (With<Player>)>,
+    //mut exit: EventWriter<AppExit>,
+) {
+    let (mut pl, pt, mut p, mut health, effects) = player.single_mut();
+    //slippery surfaces make speeding up and slowing down take longer
+    let accel = pl.friction;
+    let top_speed = PLAYER_SPEED * effects.speed_scale();
+    if input.pressed(KeyCode::A) {
+        pl.facing_left = true;
+        if pl.velocity.x > -top_speed {
+            pl.velocity.x = (pl.velocity.x - accel).max(-top_speed);
+        }
+    } else if pl.velocity.x < 0. {
+        pl.velocity.x = (pl.velocity.x + accel).min(0.);
+    }
+
+    if input.pressed(KeyCode::D) {
+        pl.facing_left = false;
+        if pl.velocity.x < top_speed {
+            pl.velocity.x = (pl.velocity.x + accel).min(top_speed);
+        }
+    } else if pl.velocity.x > 0. {
+        pl.velocity.x = (pl.velocity.x - accel).max(0.);
+    }
+
+    if pl.velocity.x == 0. && pl.velocity.y == 0. && input.pressed(KeyCode::H) && health.current < health.max {
+        if p.healing_bar == 240 {
+            //topping off the last bit doesn't empty the bar
+            if health.current <= health.max - 20 {
+                p.healing_bar = 0; 
+            }
+            health.heal(20);
+        }
+        else {
+            p.healing_bar += 1;
+        }
+    }
+
+    //hold S and jump to drop through a one way platform
+    let dropping = pl.on_one_way && input.pressed(KeyCode::S) && input.pressed(KeyCode::Space);
+    if dropping {
+        pl.drop_frames = DROP_FRAMES;
+    } else if pl.drop_frames > 0 {
+        pl.drop_frames -= 1;
+    }
+
+    let mut change = Vec2::splat(0.);
+    change.x = pl.velocity.x;
+
+    if input.just_pressed(KeyCode::J) {
+        //press to rotate item
+        p.equipped().on_unequip(&mut pl);
+        p.active_item = (p.active_item + 1) % p.items.len();
+        p.equipped().on_equip(&mut pl);
+    }
+    let item = p.equipped();
+    if pl.grounded {
+        pl.air_jumps = 0;
+        p.fuel.recharge();
+    }
+    item.on_tick(&mut pl, &mut p, &input);
+    p.dash.tick(&mut pl);
+    if input.just_pressed(KeyCode::LShift) {
+        p.dash.start(&pl);
+    }
+    //pressed into a wall in the air, with wall jumping unlocked
+    let on_wall = !pl.grounded && pl.wall != 0. && p.has_ability(WALL_JUMP);
+    if p.dash.active() {
+        p.dash.apply(&mut pl);
+    } else if on_wall && input.just_pressed(KeyCode::Space) {
+        wall_jump(&mut pl);
+    } else if input.pressed(KeyCode::Space) && !dropping {
+        item.on_activate(&mut pl, &mut p, &input);
+    } else {
+        //if the player did not just jump, the item decides how they fall
+        item.on_fall(&mut pl, &mut p, &input);
+    }
+    if on_wall && !p.dash.active() {
+        wall_slide(&mut pl);
+    }
+    change.x = pl.velocity.x;
+    change.y = pl.velocity.y;
+
+    //this holds the position the player will end up in if there is no collision
+    pl.projected_position = pt.translation + Vec3::new(change.x, change.y, 0.);
+    pl.grounded = false;
+    pl.on_one_way = false;
+}
+
+pub fn attack(
+    input: Res<TickInput>,
+    mut player: Query<(&mut ActiveObject, &mut Transform, &mut Player), With<Player>>,
+    mut objects: Query<(&mut Object, &Transform, Entity), (With<Object>, Without<Player>)>,
+    mut commands: Commands,
+) {
+    let (pl, pt, mut p) = player.single_mut();
+    p.combo.tick();
+    if input.just_pressed(KeyCode::K) {
+        let step = match p.combo.swing() {
+            Some(step) => step,
+            None => return,
+        };
+        let dir = if input.pressed(KeyCode::S) {
+            -Vec2::Y
+        } else if input.pressed(KeyCode::W) {
+            Vec2::Y
+        } else if pl.facing_left {
+            -Vec2::X
+        } else {
+            Vec2::X
+        };
+        let hitbox = Hitbox::new(step, dir);
+        //just clear of the player on the side being swung at
+        let reach = (Vec2::splat(PLAYER_SZ) + hitbox.size) / 2. * dir;
+        commands
+            .spawn_bundle(SpriteBundle {
+                transform: Transform {
+                    translation: Vec3::new(pt.translation.x + reach.x, pt.translation.y + reach.y, 0.),
+                    ..default()
+                },
+
+                ..default()
+            })
+            .insert(hitbox);
+    }
+}
+
+//bounces the player up after a down swing connects
+fn pogo(pl: &mut ActiveObject) {
+    pl.velocity.y = POGO_SPEED;
+    pl.air_jumps = 0;
+}
+
+pub fn attack_static(
+    mut objects: Query<(&mut Object, &Transform, Entity), (With<Object>, Without<Player>)>,
+    mut player: Query<&mut ActiveObject, With<Player>>,
+    mut commands: Commands,
+    mut hitbox: Query<(&mut Hitbox, &Transform, Entity), With<Hitbox>>,
+) {
+    for (hb, hb_t, hb_e) in hitbox.iter_mut() {
+        for (mut _o, t, entity) in objects.iter_mut() {
+            let res = bevy::sprite::collide_aabb::collide(
+                hb_t.translation,
+                hb.size,
+                t.translation,
+                Vec2::new(_o.width, _o.height),
+            );
+            if res.is_some() {
+                if (matches!(_o.obj_type, ObjectType::Barrel)
+                    || matches!(_o.obj_type, ObjectType::Breakable))
+                {
+                    _o.broken = true;
+                } else if hb.pogo && matches!(_o.obj_type, ObjectType::Spike) {
+                    pogo(&mut player.single_mut());
+                }
+            }
+        }
+    }
+}
+
+pub fn attack_active(
+    mut actives: Query<
+        (&mut ActiveObject, &Transform, Entity, Option<&mut Enemy>, Option<&mut StatusEffects>),
+        (With<ActiveObject>, Without<Player>, Without<Projectile>),
+    >,
+    mut player: Query<&mut ActiveObject, With<Player>>,
+    mut commands: Commands,
+    mut hitbox: Query<(&mut Hitbox, &Transform, Entity), With<Hitbox>>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    for (hb, hb_t, hb_e) in hitbox.iter_mut() {
+        for (mut a, a_t, a_e, e, effects) in actives.iter_mut() {
+            let res = bevy::sprite::collide_aabb::collide(
+                hb_t.translation,
+                hb.size,
+                a_t.translation,
+                Vec2::new(PLAYER_SZ, PLAYER_SZ),
+            );
+            if res.is_some() {
+                a.velocity = hb.knockback;
+                damage.send(DamageEvent {
+                    target: a_e,
+                    amount: hb.damage,
+                    source: Some(hb_e),
+                    kind: DamageKind::Melee,
+                });
+                if let Some(mut effects) = effects {
+                    effects.add_for(Effect::Stun, hb.stun);
+                }
+                if let Some(mut e) = e {
+                    e.interrupt();
+                }
+                if hb.pogo {
+                    pogo(&mut player.single_mut());
+                }
+            }
+        }
+        commands.entity(hb_e).despawn();
+    }
+}
+
+pub fn player_health(
+    mut commands: Commands,
+    mut deaths: EventReader<DeathEvent>,
+    player: Query<Entity, With<Player>>,
+    mut stats: ResMut<RunStats>,
+) {
+    let pe = player.single();
+    for death in deaths.iter().filter(|d| d.entity == pe) {
+        game_over(&mut commands, &mut stats, death_cause(death.kind));
+    }
+}
+
+//what the run end screen says killed the player
+fn death_cause(kind: DamageKind) -> &'static str {
+    match kind {
+        DamageKind::Melee => "Cut down by an enemy",
+        DamageKind::Projectile => "Shot down",
+        DamageKind::Explosion => "Caught in an explosion",
+        DamageKind::Debris => "Crushed by debris",
+        DamageKind::Status => "Ran out of health",
+    }
+}
+
+pub fn change_player_direction(
+    mut player: Query<(&ActiveObject, &mut Transform, &mut Player), With<Player>>,
+){
+    let (mut pl, mut pt, mut p) = player.single_mut();
+    if pl.facing_left {
+        pt.rotation = Quat::from_rotation_y(std::f32::consts::PI);
+    } else {
+        pt.rotation = Quat::default();
+    }
+}
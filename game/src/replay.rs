@@ -0,0 +1,266 @@
+use crate::active_util::*;
+use crate::difficulty::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::util::*;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+use std::fs;
+
+//only these keys are read by gameplay, each one gets a bit in a tick's key mask
+const RECORDED_KEYS: [KeyCode; 14] = [
+    KeyCode::A,
+    KeyCode::D,
+    KeyCode::W,
+    KeyCode::S,
+    KeyCode::Space,
+    KeyCode::H,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::B,
+    KeyCode::X,
+    KeyCode::C,
+    KeyCode::LShift,
+    KeyCode::Q,
+];
+
+pub fn key_mask(keys: impl Iterator<Item = KeyCode>) -> u32 {
+    let mut mask = 0;
+    for key in keys {
+        if let Some(bit) = RECORDED_KEYS.iter().position(|k| *k == key) {
+            mask |= 1 << bit;
+        }
+    }
+    mask
+}
+
+pub fn mask_keys(mask: u32) -> impl Iterator<Item = KeyCode> {
+    RECORDED_KEYS
+        .into_iter()
+        .enumerate()
+        .filter(move |(bit, _)| mask & (1 << bit) != 0)
+        .map(|(_, key)| key)
+}
+
+pub enum ReplayMode {
+    Off,
+    //path the recording gets written to as the game goes, see save_recording
+    Record(String),
+    Replay,
+}
+
+//a run is its seed, the room it started in, its difficulty, the unlocks it started with and the keys held on every tick
+//--record <file> saves one, --replay <file> plays one back, --room <id> picks the starting room
+pub struct Replay {
+    pub mode: ReplayMode,
+    pub seed: u64,
+    pub start_room: i8,
+    pub difficulty: Difficulty,
+    //Progress unlocks from the save the run started with, so playing it back doesn't depend on the save on disk
+    pub unlocks: Vec<String>,
+    pub frames: Vec<u32>,
+    pub tick: usize,
+    //checksum of the world after the latest tick
+    pub checksum: u64,
+    //checksum the recording ended with
+    pub expected: u64,
+    pub done: bool,
+    //ticks and room the recording file was last written with
+    pub flushed: usize,
+    pub flushed_room: Option<i8>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, start_room: i8) -> Self {
+        Self {
+            mode: ReplayMode::Off,
+            seed: seed,
+            start_room: start_room,
+            difficulty: Difficulty::Normal,
+            unlocks: Vec::new(),
+            frames: Vec::new(),
+            tick: 0,
+            checksum: 0,
+            expected: 0,
+            done: false,
+            flushed: 0,
+            flushed_room: None,
+        }
+    }
+
+    pub fn from_args(seed: u64) -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        let mut replay = Self::new(seed, 0);
+        let mut i = 1;
+        while i + 1 < args.len() {
+            match args[i].as_str() {
+                "--record" => replay.mode = ReplayMode::Record(args[i + 1].clone()),
+                "--room" => match args[i + 1].parse() {
+                    Ok(room) => replay.start_room = room,
+                    Err(_) => println!("Invalid room: {}", args[i + 1]),
+                },
+                "--replay" => match fs::read_to_string(&args[i + 1]) {
+                    Ok(text) => match Self::parse(&text) {
+                        Ok(loaded) => replay = loaded,
+                        Err(e) => println!("Could not read replay {}: {}", args[i + 1], e),
+                    },
+                    Err(e) => println!("Could not open replay {}: {}", args[i + 1], e),
+                },
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            }
+            i += 2;
+        }
+        replay
+    }
+
+    //header lines are "seed", "room", "difficulty", "unlocks" and "checksum", then one hex key mask per tick
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        let mut header = |name: &str| -> Result<String, String> {
+            match lines.next().and_then(|l| l.strip_prefix(name)) {
+                Some(value) => Ok(value.trim().to_string()),
+                None => Err(format!("missing {}", name)),
+            }
+        };
+        let seed: u64 = header("seed")?.parse().map_err(|_| "bad seed")?;
+        let room: i8 = header("room")?.parse().map_err(|_| "bad room")?;
+        let difficulty = Difficulty::from_name(&header("difficulty")?).ok_or("bad difficulty")?;
+        let unlocks = header("unlocks")?;
+        let checksum: u64 = header("checksum")?.parse().map_err(|_| "bad checksum")?;
+        let mut replay = Self::new(seed, room);
+        replay.difficulty = difficulty;
+        replay.unlocks = unlocks.split(',').filter(|u| !u.is_empty()).map(|u| u.to_string()).collect();
+        for line in lines {
+            let mask = u32::from_str_radix(line.trim(), 16).map_err(|_| format!("bad tick: {}", line))?;
+            replay.frames.push(mask);
+        }
+        replay.mode = ReplayMode::Replay;
+        replay.expected = checksum;
+        Ok(replay)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "seed {}\nroom {}\ndifficulty {:?}\nunlocks {}\nchecksum {}\n",
+            self.seed,
+            self.start_room,
+            self.difficulty,
+            self.unlocks.join(","),
+            self.checksum
+        );
+        for mask in &self.frames {
+            text.push_str(&format!("{:x}\n", mask));
+        }
+        text
+    }
+
+    //keys for the next tick, the live ones unless a recording is being played back
+    pub fn next_frame(&mut self, live: u32) -> u32 {
+        match self.mode {
+            ReplayMode::Off => live,
+            ReplayMode::Record(_) => {
+                self.frames.push(live);
+                live
+            }
+            ReplayMode::Replay => {
+                let mask = self.frames.get(self.tick).copied().unwrap_or(0);
+                self.tick += 1;
+                mask
+            }
+        }
+    }
+}
+
+fn mix(hash: u64, value: u64) -> u64 {
+    //FNV-1a, one word at a time
+    (hash ^ value).wrapping_mul(0x100000001b3)
+}
+
+fn mix_vec(hash: u64, v: Vec3) -> u64 {
+    let hash = mix(hash, v.x.to_bits() as u64);
+    let hash = mix(hash, v.y.to_bits() as u64);
+    mix(hash, v.z.to_bits() as u64)
+}
+
+//runs last every tick, when a replay runs out of ticks its final checksum is compared to the recorded one
+pub fn checksum_world(
+    count: Res<TickCount>,
+    mut replay: ResMut<Replay>,
+    actives: Query<(&ActiveObject, &Transform, Option<&Health>)>,
+    objects: Query<&Object>,
+    player: Query<&Player>,
+    manager: Query<&Manager>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    hash = mix(hash, count.ticks);
+    for m in manager.iter() {
+        hash = mix(hash, m.room_number as u64);
+    }
+    for p in player.iter() {
+        hash = mix(hash, p.credits as u64);
+        hash = mix(hash, p.active_item as u64);
+        hash = mix(hash, p.items.len() as u64);
+    }
+    for (a, t, health) in actives.iter() {
+        hash = mix_vec(hash, t.translation);
+        hash = mix_vec(hash, a.velocity.extend(0.));
+        hash = mix(hash, health.map_or(0, |h| h.current) as u64);
+    }
+    for o in objects.iter() {
+        hash = mix(hash, o.obj_type as u64);
+        hash = mix(hash, o.broken as u64);
+    }
+    replay.checksum = hash;
+
+    if matches!(replay.mode, ReplayMode::Replay) && !replay.done && replay.tick >= replay.frames.len() {
+        replay.done = true;
+        if replay.checksum == replay.expected {
+            println!("Replay finished after {} ticks, checksum matches", replay.tick);
+        } else {
+            println!(
+                "Replay DESYNC after {} ticks: expected checksum {} but got {}",
+                replay.tick, replay.expected, replay.checksum
+            );
+        }
+        exit.send(AppExit);
+    }
+}
+
+//ticks between writes of the recording, so a crash loses at most this much of it
+const FLUSH_TICKS: usize = 600;
+
+//writes the recording out every so often, on every room change, when a run ends and when the game closes
+pub fn save_recording(
+    mut replay: ResMut<Replay>,
+    difficulty: Res<Difficulty>,
+    mut exits: EventReader<AppExit>,
+    manager: Query<&Manager>,
+    state: Res<CurrentState<GameState>>,
+) {
+    let path = match &replay.mode {
+        ReplayMode::Record(path) => path.clone(),
+        _ => return,
+    };
+    let closing = exits.iter().next().is_some();
+    let room = manager.iter().next().map(|m| m.room_number);
+    let run_over = state.is_changed() && matches!(state.0, GameState::GameOver | GameState::Victory);
+    let due = replay.frames.len() >= replay.flushed + FLUSH_TICKS || room != replay.flushed_room || run_over;
+    if !closing && !due {
+        return;
+    }
+    replay.difficulty = *difficulty;
+    match fs::write(&path, replay.to_text()) {
+        Ok(_) if closing => println!("Saved {} ticks to {}", replay.frames.len(), path),
+        Ok(_) => {}
+        Err(e) => println!("Could not save recording to {}: {}", path, e),
+    }
+    replay.flushed = replay.frames.len();
+    replay.flushed_room = room;
+}
@@ -0,0 +1,230 @@
+use crate::active_util::*;
+use crate::difficulty::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::items::no_item::*;
+use crate::items::*;
+use crate::level::*;
+use crate::replay::*;
+use crate::util::*;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use iyes_loopless::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+//bump this when the format changes, and add a step to migrate() that upgrades the version before it
+pub const SAVE_VERSION: u32 = 2;
+
+//everything a save file holds
+//a save is "key value" lines, starting with the version
+pub struct SaveData {
+    pub credits: i32,
+    pub health: i32,
+    pub items: Vec<&'static str>,
+    pub active_item: usize,
+    pub room: i8,
+    pub discovered: Vec<i8>,
+    pub unlocks: Vec<String>,
+    pub difficulty: Difficulty,
+}
+
+impl SaveData {
+    pub fn to_text(&self) -> String {
+        let join = |v: Vec<String>| v.join(" ");
+        let mut text = format!("version {}\n", SAVE_VERSION);
+        text.push_str(&format!("credits {}\n", self.credits));
+        text.push_str(&format!("health {}\n", self.health));
+        text.push_str(&format!("items {}\n", join(self.items.iter().map(|i| i.to_string()).collect())));
+        text.push_str(&format!("active_item {}\n", self.active_item));
+        text.push_str(&format!("room {}\n", self.room));
+        text.push_str(&format!("discovered {}\n", join(self.discovered.iter().map(|r| r.to_string()).collect())));
+        text.push_str(&format!("unlocks {}\n", self.unlocks.join(" ")));
+        text.push_str(&format!("difficulty {:?}\n", self.difficulty));
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut fields: HashMap<String, String> = HashMap::default();
+        for line in text.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            fields.insert(key.to_string(), value.trim().to_string());
+        }
+        let version: u32 = match fields.get("version") {
+            Some(v) => v.parse().map_err(|_| "bad version")?,
+            None => return Err("missing version".to_string()),
+        };
+        migrate(version, &mut fields)?;
+
+        let get = |key: &str| fields.get(key).cloned().unwrap_or_default();
+        let number = |key: &str| -> Result<i64, String> {
+            get(key).parse().map_err(|_| format!("bad {}", key))
+        };
+        let mut items = Vec::new();
+        for name in get("items").split_whitespace() {
+            items.push(find_item(name).ok_or(format!("unknown item {}", name))?.name());
+        }
+        if items.is_empty() {
+            items.push(NO_ITEM);
+        }
+        let mut discovered: Vec<i8> = Vec::new();
+        for room in get("discovered").split_whitespace() {
+            discovered.push(room.parse().map_err(|_| "bad discovered room")?);
+        }
+        let active_item = (number("active_item")? as usize).min(items.len() - 1);
+        Ok(Self {
+            credits: number("credits")? as i32,
+            health: number("health")? as i32,
+            items: items,
+            active_item: active_item,
+            room: number("room")? as i8,
+            discovered: discovered,
+            unlocks: get("unlocks").split_whitespace().map(|u| u.to_string()).collect(),
+            difficulty: Difficulty::from_name(&get("difficulty")).ok_or("bad difficulty")?,
+        })
+    }
+}
+
+//upgrades the fields of an older save to the current version
+fn migrate(version: u32, fields: &mut HashMap<String, String>) -> Result<(), String> {
+    if version == 0 || version > SAVE_VERSION {
+        return Err(format!("unsupported save version {}", version));
+    }
+    //each format change adds a step here that rewrites the fields of older saves, oldest first
+    //saves from before difficulties were all played on Normal
+    if version < 2 {
+        fields.insert("difficulty".to_string(), "Normal".to_string());
+    }
+    fields.insert("version".to_string(), SAVE_VERSION.to_string());
+    Ok(())
+}
+
+//where the save goes on this platform
+pub fn save_path() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    let data_dir = if cfg!(target_os = "windows") {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+    };
+    data_dir.map(|dir| dir.join("team1_game").join("save.txt"))
+}
+
+//the save found when the game started, if there was one
+pub struct SaveSlot {
+    pub path: Option<PathBuf>,
+    pub data: Option<SaveData>,
+}
+
+impl SaveSlot {
+    pub fn load() -> Self {
+        let path = save_path();
+        let data = match &path {
+            Some(p) if p.exists() => match fs::read_to_string(p).map_err(|e| e.to_string()).and_then(|t| SaveData::parse(&t)) {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    println!("Could not load save {}: {}", p.display(), e);
+                    None
+                }
+            },
+            _ => None,
+        };
+        Self { path: path, data: data }
+    }
+}
+
+//sent from the main menu to pick up where the save left off
+pub struct ContinueGame;
+
+//writes a save on every SaveGame and restores one on ContinueGame
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        let slot = SaveSlot::load();
+        let mut replay = app.world.resource_mut::<Replay>();
+        if matches!(replay.mode, ReplayMode::Replay) {
+            //a replay starts with the unlocks it was recorded with, whatever the save says now
+            let mut progress = Progress::new();
+            progress.unlocks = replay.unlocks.clone();
+            app.insert_resource(progress);
+        } else if let Some(data) = &slot.data {
+            //discovered rooms and unlocks apply even when starting a new game
+            replay.unlocks = data.unlocks.clone();
+            let mut progress = Progress::new();
+            progress.discovered = data.discovered.clone();
+            progress.unlocks = data.unlocks.clone();
+            app.insert_resource(progress);
+        }
+        app.insert_resource(slot)
+            .add_event::<ContinueGame>()
+            .add_system(write_save)
+            .add_system(continue_game);
+    }
+}
+
+pub fn write_save(
+    mut saves: EventReader<SaveGame>,
+    mut slot: ResMut<SaveSlot>,
+    replay: Res<Replay>,
+    player: Query<(&Player, &Health)>,
+    manager: Query<&Manager>,
+    progress: Res<Progress>,
+    difficulty: Res<Difficulty>,
+) {
+    if saves.iter().count() == 0 || matches!(replay.mode, ReplayMode::Replay) {
+        return;
+    }
+    let (p, health) = player.single();
+    let data = SaveData {
+        credits: p.credits,
+        health: health.current,
+        items: p.items.clone(),
+        active_item: p.active_item,
+        room: manager.single().room_number,
+        discovered: progress.discovered.clone(),
+        unlocks: progress.unlocks.clone(),
+        difficulty: *difficulty,
+    };
+    if let Some(path) = &slot.path {
+        let written = match path.parent() {
+            Some(dir) => fs::create_dir_all(dir).and_then(|_| fs::write(path, data.to_text())),
+            None => fs::write(path, data.to_text()),
+        };
+        if let Err(e) = written {
+            println!("Could not save to {}: {}", path.display(), e);
+        }
+    }
+    slot.data = Some(data);
+}
+
+pub fn continue_game(
+    mut commands: Commands,
+    mut continues: EventReader<ContinueGame>,
+    slot: Res<SaveSlot>,
+    mut player: Query<(&mut Player, &mut Health)>,
+    mut manager: Query<&mut Manager>,
+    mut difficulty: ResMut<Difficulty>,
+    mut clock: ResMut<Clock>,
+) {
+    if continues.iter().count() == 0 {
+        return;
+    }
+    if let Some(data) = &slot.data {
+        let (mut p, mut health) = player.single_mut();
+        p.credits = data.credits;
+        health.current = data.health;
+        p.items = data.items.clone();
+        p.active_item = data.active_item;
+        apply_unlocks(&mut p, &data.unlocks);
+        let mut m = manager.single_mut();
+        m.prev_room_number = -1;
+        m.room_number = data.room;
+        m.spawn = "start";
+        *difficulty = data.difficulty;
+        clock.restart(difficulty.start_time());
+        commands.insert_resource(NextState(GameState::Playing));
+    }
+}
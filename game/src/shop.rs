@@ -0,0 +1,167 @@
+use crate::active_util::*;
+use crate::difficulty::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::items::jetpack::*;
+use crate::items::*;
+use crate::physics::*;
+use crate::timer::*;
+use crate::util::*;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+//room 0, where credits get spent on items
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_gameplay_system(item_shop.run_in_state(GameState::Playing).label(Step::Movement).label(GameplaySystem::ItemShop).after(GameplaySystem::Shoot));
+    }
+}
+
+//sprites shown over each item on sale, stocked once per visit
+#[derive(Component)]
+pub struct ShopDisplay;
+
+//space between the items on display, the row is centered in the shop
+const SHOP_SPACING: f32 = 130.;
+
+//every item on sale, then extra time and the fuel tank upgrade in the two slots after the last item
+fn shop_slots() -> usize {
+    items_for_sale().count() + 2
+}
+
+//where the shop shows the item at `slot`
+pub fn shop_slot_x(slot: usize) -> f32 {
+    (slot as f32 - (shop_slots() - 1) as f32 / 2.) * SHOP_SPACING
+}
+
+//the slot whose sprite is closest to x
+pub fn shop_slot_at(x: f32) -> usize {
+    let slot = (x / SHOP_SPACING + (shop_slots() - 1) as f32 / 2. + 0.5).floor();
+    (slot.max(0.) as usize).min(shop_slots() - 1)
+}
+
+pub fn item_shop(
+    input: Res<TickInput>,
+    mut player: Query<(&mut Player, &mut Transform), With<Player>>,
+    mut clock: ResMut<Clock>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut manager: Query<&mut Manager, (With<Manager>)>,
+    mut progress: ResMut<Progress>,
+    rules: Res<TimerRules>,
+    mut timer_events: EventWriter<TimerEvent>,
+    difficulty: Res<Difficulty>,
+    display: Query<(), With<ShopDisplay>>,
+) {
+    let extra_time_price = difficulty.price(EXTRA_TIME_PRICE);
+    let mut m = manager.single_mut();
+    let (mut p, mut pt) = player.single_mut();
+    if m.room_number == 0 {
+        clock.timer.pause();
+        //pt.translation = Vec3::new(0., -575., 0.);
+
+        //the displays are objects, so leaving the room clears them and the next visit restocks
+        if display.is_empty() {
+            let id = 0;
+            let mut slots = 0;
+            for (slot, item) in items_for_sale().enumerate() {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: item.tint(),
+                            custom_size: Some(Vec2::new(75., 75.)),
+                            ..default()
+                        },
+                        texture: asset_server.load(item.texture()),
+                        transform: Transform {
+                            translation: Vec3::new(shop_slot_x(slot), -400., 2.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(Object::new(id, 50., 50., ObjectType::Active))
+                    .insert(ShopDisplay);
+                slots += 1;
+            }
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::GOLD,
+                        custom_size: Some(Vec2::new(50., 50.)),
+                        ..default()
+                    },
+                    transform: Transform {
+                        translation: Vec3::new(shop_slot_x(slots), -400., 2.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(Object::new(id, 50., 50., ObjectType::Active))
+                .insert(ShopDisplay);
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::ORANGE_RED,
+                        custom_size: Some(Vec2::new(75., 75.)),
+                        ..default()
+                    },
+                    texture: asset_server.load("jetpack.png"),
+                    transform: Transform {
+                        translation: Vec3::new(shop_slot_x(slots + 1), -400., 2.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(Object::new(id, 50., 50., ObjectType::Active))
+                .insert(ShopDisplay);
+        }
+    } 
+    
+    if pt.translation.y <= -400. {
+        if input.just_pressed(KeyCode::B) {
+            let slot = shop_slot_at(pt.translation.x);
+            if let Some(item) = items_for_sale().nth(slot) {
+                let price = difficulty.price(item.price().unwrap());
+                if p.credits >= price {
+                    if p.items.contains(&item.name()) {
+                        println!("{} already purchased!", item.name());
+                    } else {
+                        p.credits -= price;
+                        p.items.push(item.name());
+                        //bought once, owned at the start of every run after this
+                        progress.unlock(item.name());
+                        print!("{} PURCHASED!", item.name().to_uppercase());
+                    }
+                } else {
+                    println!("Insufficient funds.");
+                }
+            } else if slot == items_for_sale().count() {
+                //IF TRY TO BUY EXTRA TIME, can be bought as often as it can be afforded
+                if p.credits >= extra_time_price {
+                    p.credits -= extra_time_price;
+                    clock.add_time(rules.purchase_time);
+                    timer_events.send(TimerEvent::Extended(rules.purchase_time));
+                    print!("EXTRA TIME PURCHASED!");
+                } else {
+                    println!("Insufficient funds.");
+                }
+            } else {
+                //IF TRY TO UPGRADE THE FUEL TANK, one tier at a time
+                match p.fuel.next_tank_price().map(|price| difficulty.price(price)) {
+                    None => println!("Fuel tank fully upgraded!"),
+                    Some(_) if !p.items.contains(&JETPACK) => println!("Buy the jetpack first!"),
+                    Some(price) if p.credits >= price => {
+                        p.credits -= price;
+                        p.fuel.upgrade();
+                        progress.unlock(&fuel_tank_unlock(p.fuel.tank));
+                        print!("FUEL TANK UPGRADED!");
+                    }
+                    Some(_) => println!("Insufficient funds."),
+                }
+            }
+            println!("PRESS I TO RETURN!");
+        }
+    }
+}
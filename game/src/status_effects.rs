@@ -0,0 +1,288 @@
+use crate::active_util::*;
+use crate::ai::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::health::*;
+use crate::physics::*;
+use crate::util::*;
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+//lingering effects on the player and enemies, each one stacks up to a limit and wears off after a while
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    Burn,
+    Slow,
+    Stun,
+    Poison,
+    Bleed,
+}
+
+pub const EFFECT_KINDS: usize = 5;
+//in Effect order, which is also the order their icons line up in
+pub const EFFECTS: [Effect; EFFECT_KINDS] = [Effect::Burn, Effect::Slow, Effect::Stun, Effect::Poison, Effect::Bleed];
+
+pub struct EffectInfo {
+    pub name: &'static str,
+    pub color: Color,
+    pub max_stacks: u32,
+    //ticks it lasts, adding a stack starts them over
+    pub ticks: u32,
+    //damage per stack every `interval` ticks
+    pub damage: i32,
+    pub interval: u32,
+    //fraction of movement speed taken away per stack
+    pub slow: f32,
+    //extra fraction of damage taken per stack
+    pub weakness: f32,
+}
+
+pub static EFFECT_INFO: [EffectInfo; EFFECT_KINDS] = [
+    //explosions
+    EffectInfo {
+        name: "Burn",
+        color: Color::ORANGE_RED,
+        max_stacks: 3,
+        ticks: 120,
+        damage: 2,
+        interval: 15,
+        slow: 0.,
+        weakness: 0.,
+    },
+    //cobwebs, lingering for a moment after getting out
+    EffectInfo {
+        name: "Slow",
+        color: Color::CYAN,
+        max_stacks: 3,
+        ticks: 45,
+        damage: 0,
+        interval: 1,
+        slow: 0.25,
+        weakness: 0.,
+    },
+    //melee hits, stunned enemies stop deciding what to do and can't move themselves
+    EffectInfo {
+        name: "Stun",
+        color: Color::YELLOW,
+        max_stacks: 1,
+        ticks: 30,
+        damage: 0,
+        interval: 1,
+        slow: 1.,
+        weakness: 0.,
+    },
+    //ranged enemies' shots
+    EffectInfo {
+        name: "Poison",
+        color: Color::LIME_GREEN,
+        max_stacks: 5,
+        ticks: 300,
+        damage: 1,
+        interval: 30,
+        slow: 0.,
+        weakness: 0.1,
+    },
+    //spikes
+    EffectInfo {
+        name: "Bleed",
+        color: Color::MAROON,
+        max_stacks: 5,
+        ticks: 180,
+        damage: 1,
+        interval: 12,
+        slow: 0.,
+        weakness: 0.,
+    },
+];
+
+impl Effect {
+    pub fn info(self) -> &'static EffectInfo {
+        &EFFECT_INFO[self as usize]
+    }
+}
+
+struct ActiveEffect {
+    effect: Effect,
+    stacks: u32,
+    ticks: u32,
+    //ticks since it was first applied, for damage every `interval` ticks
+    age: u32,
+}
+
+#[derive(Component)]
+pub struct StatusEffects {
+    active: Vec<ActiveEffect>,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self { active: Vec::new() }
+    }
+    pub fn add(&mut self, effect: Effect) {
+        self.add_for(effect, effect.info().ticks);
+    }
+    //one more stack, lasting at least `ticks`
+    pub fn add_for(&mut self, effect: Effect, ticks: u32) {
+        match self.active.iter_mut().find(|a| a.effect == effect) {
+            Some(a) => {
+                a.stacks = (a.stacks + 1).min(effect.info().max_stacks);
+                a.ticks = a.ticks.max(ticks);
+            }
+            None => self.active.push(ActiveEffect {
+                effect: effect,
+                stacks: 1,
+                ticks: ticks,
+                age: 0,
+            }),
+        }
+    }
+    //keeps an effect going while its source lasts without stacking it, like standing in a cobweb
+    pub fn keep(&mut self, effect: Effect) {
+        if self.has(effect) {
+            for a in self.active.iter_mut().filter(|a| a.effect == effect) {
+                a.ticks = a.ticks.max(effect.info().ticks);
+            }
+        } else {
+            self.add(effect);
+        }
+    }
+    pub fn stacks(&self, effect: Effect) -> u32 {
+        self.active.iter().find(|a| a.effect == effect).map_or(0, |a| a.stacks)
+    }
+    pub fn has(&self, effect: Effect) -> bool {
+        self.stacks(effect) > 0
+    }
+    pub fn stunned(&self) -> bool {
+        self.has(Effect::Stun)
+    }
+    //times normal movement speed
+    pub fn speed_scale(&self) -> f32 {
+        self.active
+            .iter()
+            .map(|a| (1. - a.effect.info().slow * a.stacks as f32).max(0.))
+            .product()
+    }
+    //how much `damage` actually hurts with the current effects
+    pub fn hurt(&self, damage: i32) -> i32 {
+        let weakness: f32 = self.active.iter().map(|a| a.effect.info().weakness * a.stacks as f32).sum();
+        (damage as f32 * (1. + weakness)).round() as i32
+    }
+    //counts every effect down a tick, returning the damage they deal this tick
+    pub fn tick(&mut self) -> i32 {
+        let mut damage = 0;
+        for a in self.active.iter_mut() {
+            let info = a.effect.info();
+            a.age += 1;
+            if info.damage > 0 && a.age % info.interval == 0 {
+                damage += info.damage * a.stacks as i32;
+            }
+            a.ticks = a.ticks.saturating_sub(1);
+        }
+        self.active.retain(|a| a.ticks > 0);
+        damage
+    }
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+}
+
+//part of the health bar and effect icons floating over an enemy
+#[derive(Component)]
+struct EnemyBar {
+    owner: Entity,
+    part: BarPart,
+}
+
+enum BarPart {
+    Back,
+    Fill,
+    Icon(Effect),
+}
+
+const ICON_SZ: f32 = 8.;
+
+//status effects wearing off and hurting, and their icons over enemies
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_gameplay_system(tick_status_effects.run_in_state(GameState::Playing).label(Step::Combat).label(GameplaySystem::TickStatusEffects).after(GameplaySystem::ProjectileStaticCollisions).after(Step::Movement))
+            .add_system(spawn_enemy_bars.run_in_state(GameState::Playing))
+            .add_system(draw_enemy_bars.run_in_state(GameState::Playing));
+    }
+}
+
+pub fn tick_status_effects(mut actives: Query<(Entity, &mut StatusEffects)>, mut damage: EventWriter<DamageEvent>) {
+    for (entity, mut effects) in actives.iter_mut() {
+        let amount = effects.tick();
+        if amount > 0 {
+            damage.send(DamageEvent {
+                target: entity,
+                amount: amount,
+                source: None,
+                kind: DamageKind::Status,
+            });
+        }
+    }
+}
+
+fn spawn_enemy_bars(mut commands: Commands, enemies: Query<Entity, Added<Enemy>>) {
+    for owner in enemies.iter() {
+        let mut part = |part: BarPart, color: Color, size: Vec2| {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: color,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(EnemyBar { owner: owner, part: part });
+        };
+        part(BarPart::Back, Color::rgba(0., 0., 0., 0.6), HEALTHBAR_SZ);
+        part(BarPart::Fill, Color::RED, HEALTHBAR_SZ);
+        for effect in EFFECTS {
+            part(BarPart::Icon(effect), effect.info().color, Vec2::splat(ICON_SZ));
+        }
+    }
+}
+
+//follows each enemy, bars whose enemy is gone go with it
+fn draw_enemy_bars(
+    mut commands: Commands,
+    enemies: Query<(&Transform, &Health, &StatusEffects), With<Enemy>>,
+    mut bars: Query<(Entity, &EnemyBar, &mut Transform, &mut Sprite, &mut Visibility), Without<Enemy>>,
+) {
+    for (e, bar, mut t, mut sprite, mut visibility) in bars.iter_mut() {
+        let (et, health, effects) = match enemies.get(bar.owner) {
+            Ok(enemy) => enemy,
+            Err(_) => {
+                commands.entity(e).despawn();
+                continue;
+            }
+        };
+        let above = et.translation + Vec3::new(0., PLAYER_SZ / 2. + 8., 10.);
+        match bar.part {
+            BarPart::Back => t.translation = above,
+            BarPart::Fill => {
+                let fraction = health.fraction();
+                sprite.custom_size = Some(Vec2::new(HEALTHBAR_SZ.x * fraction, HEALTHBAR_SZ.y));
+                t.translation = above + Vec3::new(-HEALTHBAR_SZ.x * (1. - fraction) / 2., 0., 1.);
+            }
+            BarPart::Icon(effect) => {
+                //the effects the enemy has line up left to right over the bar
+                let slot = EFFECTS.iter().filter(|e| effects.has(**e)).position(|e| *e == effect);
+                visibility.is_visible = slot.is_some();
+                if let Some(slot) = slot {
+                    t.translation = above
+                        + Vec3::new(
+                            (ICON_SZ - HEALTHBAR_SZ.x) / 2. + slot as f32 * (ICON_SZ + 2.),
+                            (HEALTHBAR_SZ.y + ICON_SZ) / 2. + 2.,
+                            1.,
+                        );
+                }
+            }
+        }
+    }
+}
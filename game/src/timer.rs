@@ -0,0 +1,131 @@
+use crate::active_util::*;
+use crate::difficulty::*;
+use crate::fixed_step::*;
+use crate::game_state::*;
+use crate::level::*;
+use crate::util::*;
+use bevy::prelude::*;
+use bevy::sprite::collide_aabb::collide;
+use iyes_loopless::prelude::*;
+
+//what a bought "extra time" costs in the shop
+pub const EXTRA_TIME_PRICE: i32 = 40;
+
+//how the run clock behaves, the time a run starts with comes from the Difficulty
+pub struct TimerRules {
+    //seconds a time pickup adds
+    pub pickup_time: f32,
+    //seconds extra time from the shop adds
+    pub purchase_time: f32,
+    //a warning goes out when the time left drops to each of these
+    pub warnings: Vec<f32>,
+    //X and C pause and unpause the clock, only for testing rooms
+    pub dev_mode: bool,
+}
+
+impl TimerRules {
+    pub fn new(dev_mode: bool) -> Self {
+        Self {
+            pickup_time: 15.,
+            purchase_time: 30.,
+            warnings: vec![30., 10., 5.],
+            dev_mode: dev_mode,
+        }
+    }
+}
+
+//anything that changes the run clock other than it ticking down
+pub enum TimerEvent {
+    //this many seconds are left
+    Warning(f32),
+    //this many seconds were added
+    Extended(f32),
+    //time ran out and the player was sent back to the shop
+    Expired,
+}
+
+//GAME_DEV=1 turns on the debug keys
+pub fn dev_mode_from_env() -> bool {
+    std::env::var("GAME_DEV").map_or(false, |v| v == "1")
+}
+
+//runs the clock that sends the player back to the shop
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<TimerRules>() {
+            app.insert_resource(TimerRules::new(dev_mode_from_env()));
+        }
+        app.add_event::<TimerEvent>()
+            .add_gameplay_system(collect_time_pickups.run_in_state(GameState::Playing).label(Step::Level).label(GameplaySystem::CollectTimePickups).after(GameplaySystem::CollectAbilityPickups))
+            .add_gameplay_system(tick_clock.run_in_state(GameState::Playing).label(Step::Level).label(GameplaySystem::TickClock).after(GameplaySystem::CollectCredits));
+    }
+}
+
+pub fn collect_time_pickups(
+    mut commands: Commands,
+    player: Query<(&Transform, &Object), With<Player>>,
+    pickups: Query<(Entity, &Transform, &Object), Without<Player>>,
+    mut clock: ResMut<Clock>,
+    rules: Res<TimerRules>,
+    mut events: EventWriter<TimerEvent>,
+) {
+    let (pt, po) = player.single();
+    for (e, t, o) in pickups.iter() {
+        if !matches!(o.obj_type, ObjectType::TimePickup) {
+            continue;
+        }
+        if collide(pt.translation, Vec2::new(po.width, po.height), t.translation, Vec2::new(o.width, o.height)).is_some() {
+            //picked up pickups stay gone until the clock runs out, like anything else destroyed in a room
+            commands.entity(e).despawn();
+            clock.add_time(rules.pickup_time);
+            events.send(TimerEvent::Extended(rules.pickup_time));
+        }
+    }
+}
+
+pub fn tick_clock(
+    input: Res<TickInput>,
+    player: Query<&Transform, With<Player>>,
+    mut clock: ResMut<Clock>,
+    mut manager: Query<&mut Manager, (With<Manager>)>,
+    mut memory: ResMut<RoomMemory>,
+    rules: Res<TimerRules>,
+    difficulty: Res<Difficulty>,
+    mut events: EventWriter<TimerEvent>,
+) {
+    let mut m = manager.single_mut();
+    let pt = player.single();
+    let before = clock.remaining();
+    if pt.translation.y < -400. {
+        clock.timer.pause();
+    } else {
+        clock.timer.tick(TICK);
+    }
+
+    //Press X to pause the timer, press c to unpause it
+    if rules.dev_mode {
+        if input.pressed(KeyCode::X) {
+            clock.timer.pause();
+        }
+        if input.pressed(KeyCode::C) {
+            clock.timer.unpause();
+        }
+    }
+    if clock.timer.finished() {
+        println!("You ran out of time");
+        m.go_to(0, "start");
+        clock.restart(difficulty.start_time());
+        //every room comes back when the clock resets
+        memory.clear();
+        events.send(TimerEvent::Expired);
+        return;
+    }
+    let after = clock.remaining();
+    for warning in &rules.warnings {
+        if before > *warning && after <= *warning {
+            events.send(TimerEvent::Warning(*warning));
+        }
+    }
+}
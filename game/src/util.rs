@@ -1,4 +1,5 @@
 use crate::physics::*;
+use crate::status_effects::*;
 use bevy::prelude::*;
 use std::cmp::Eq;
 use std::hash::{Hash, Hasher};
@@ -14,10 +15,6 @@ pub const TERMINAL_VELOCITY: f32 = -500.;
 pub const TILE_SIZE: f32 = 32.;
 pub const UMBRELLA_VELOCITY: f32 = -0.75;
 
-pub const UMBRELLA_PRICE: i32 = 30;
-pub const JETPACK_PRICE: i32 = 70;
-pub const BOOTS_PRICE: i32 = 30;
-
 pub const ATTACK_HITBOX: Vec2 = Vec2::new(32., 16.);
 pub const HEALTHBAR_SZ: Vec2 = Vec2::new(50., 6.);
 
@@ -25,6 +22,8 @@ pub const HEALTHBAR_SZ: Vec2 = Vec2::new(50., 6.);
 pub struct Manager {
     pub room_number: i8,
     pub prev_room_number: i8,
+    //name of the spawn marker the player arrives at in room_number
+    pub spawn: &'static str,
 }
 
 impl Manager {
@@ -32,8 +31,15 @@ impl Manager {
         Self {
             room_number: o,
             prev_room_number: n,
+            spawn: "start",
         }
     }
+    //sends the player to `spawn` in `room`, create_level does the rest
+    pub fn go_to(&mut self, room: i8, spawn: &'static str) {
+        self.prev_room_number = self.room_number;
+        self.room_number = room;
+        self.spawn = spawn;
+    }
 }
 
 #[derive(Component)]
@@ -48,6 +54,7 @@ pub struct Object {
     pub velocity: Vec2,
     pub broken: bool,
     pub level: i8,
+    pub spawn: &'static str,
 }
 
 impl Object {
@@ -61,9 +68,10 @@ impl Object {
             // project_pos: Vec3::splat(0.),
             broken: false,
             level: -50,
+            spawn: "",
         }
     }
-    pub fn new2(i: i32, w: f32, h: f32, t: ObjectType, l: i8) -> Self {
+    pub fn new2(i: i32, w: f32, h: f32, t: ObjectType, l: i8, s: &'static str) -> Self {
         Self {
             id: i,
             width: w,
@@ -73,6 +81,7 @@ impl Object {
             // project_pos: Vec3::splat(0.),
             broken: false,
             level: l,
+            spawn: s,
         }
     }
 }
@@ -109,7 +118,9 @@ pub struct Descriptor {
     pub y_pos: f32,
     pub obj_type: ObjectType,
     pub id: i32,
+    //teleporters send the player to the spawn marker named `spawn` in room `level`
     pub level: i8,
+    pub spawn: &'static str,
 }
 impl Descriptor {
     fn new(w: f32, h: f32, x: f32, y: f32, t: ObjectType) -> Self {
@@ -121,6 +132,7 @@ impl Descriptor {
             obj_type: t,
             id: -50,
             level: -50,
+            spawn: "",
         }
     }
     pub fn new2(w: f32, h: f32, x: f32, y: f32, t: ObjectType, i: i32) -> Self {
@@ -132,9 +144,10 @@ impl Descriptor {
             obj_type: t,
             id: i,
             level: -50,
+            spawn: "",
         }
     }
-    pub fn new3(w: f32, h: f32, x: f32, y: f32, t: ObjectType, l: i8) -> Self {
+    pub fn new3(w: f32, h: f32, x: f32, y: f32, t: ObjectType, l: i8, s: &'static str) -> Self {
         Self {
             width: w * 32.,
             height: h * 32.,
@@ -143,10 +156,94 @@ impl Descriptor {
             obj_type: t,
             id: -50,
             level: l,
+            spawn: s,
         }
     }
 }
 
+//PingPong platforms reverse at the last waypoint, Loop platforms go from the last waypoint back to the first
+#[derive(Copy, Clone)]
+pub enum PathMode {
+    PingPong,
+    Loop,
+}
+
+//moving platforms need a list of waypoints, so they are described separately from the other objects in a room
+#[derive(Clone)]
+pub struct PlatformDescriptor {
+    pub width: f32,
+    pub height: f32,
+    pub waypoints: Vec<Vec2>,
+    pub speed: f32,
+    pub pause: u32,
+    pub mode: PathMode,
+}
+impl PlatformDescriptor {
+    //sizes and waypoints are in tiles, speed is in pixels per tick and pause is in ticks
+    fn new(w: f32, h: f32, points: &[(f32, f32)], s: f32, p: u32, m: PathMode) -> Self {
+        Self {
+            width: w * 32.,
+            height: h * 32.,
+            waypoints: points.iter().map(|(x, y)| Vec2::new(x * 32., y * 32.)).collect(),
+            speed: s,
+            pause: p,
+            mode: m,
+        }
+    }
+}
+
+//a named place in a room where the player arrives, teleporters point at one of these by name
+#[derive(Copy, Clone)]
+pub struct SpawnMarker {
+    pub name: &'static str,
+    pub x_pos: f32,
+    pub y_pos: f32,
+    //whether the player keeps their velocity when arriving here or arrives standing still
+    pub keep_velocity: bool,
+}
+impl SpawnMarker {
+    //positions are in tiles
+    fn new(n: &'static str, x: f32, y: f32, k: bool) -> Self {
+        Self {
+            name: n,
+            x_pos: x * 32.,
+            y_pos: y * 32.,
+            keep_velocity: k,
+        }
+    }
+}
+
+pub fn get_level_platforms(id: i8) -> Vec<PlatformDescriptor> {
+    let mut result = Vec::new();
+    if id == 1 {
+        //platform sliding back and forth under the ceiling
+        result.push(PlatformDescriptor::new(
+            3.,
+            1.,
+            &[(-8., 13.5), (8., 13.5)],
+            2.,
+            90,
+            PathMode::PingPong,
+        ));
+    }
+    return result;
+}
+
+//every room has a "start" marker, used for new runs, continues and anything that does not name a marker
+pub fn get_level_spawns(id: i8) -> Vec<SpawnMarker> {
+    let mut result = Vec::new();
+    if id == 0 {
+        result.push(SpawnMarker::new("start", 0., -18., false));
+    } else {
+        result.push(SpawnMarker::new("start", 0., 10.5, false));
+        //just inside the teleporters, on the bottom floor
+        result.push(SpawnMarker::new("left", -27., -11., true));
+        //the right teleporters of rooms 5 and 7 lead into each other, keeping velocity would run the player straight back out
+        result.push(SpawnMarker::new("right", 27., -11., id != 5 && id != 7));
+    }
+    return result;
+}
+
 pub fn get_level(id: i8) -> Vec<Descriptor> {
     let mut result = Vec::new();
     println!("{}",id);
@@ -170,8 +267,9 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 1., -13.5, 8.5, ObjectType::Block));
         result.push(Descriptor::new(1., 1., 13.5, 8.5, ObjectType::Block));
         //ledges under main panel
-        result.push(Descriptor::new(6., 1., -16., 7.5, ObjectType::Block));
+        result.push(Descriptor::new(6., 1., -16., 7.5, ObjectType::Goo));
         result.push(Descriptor::new(6., 1., 16., 7.5, ObjectType::Block));
+        result.push(Descriptor::new(1., 1., 17., 8.5, ObjectType::WallJumpPickup));
         //smaller lowered panels
         result.push(Descriptor::new(4., 1., -24., 5.5, ObjectType::Block));
         result.push(Descriptor::new(4., 1., 24., 5.5, ObjectType::Block));
@@ -190,10 +288,18 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 1., -20., 10., ObjectType::OtherEnemy));
         // result.push(Descriptor::new(1., 1., 27.5, 12.5, ObjectType::Breakable));
 
-        //left and right floors
-        result.push(Descriptor::new(27., 1., -16.5, 2.5, ObjectType::Block));
+        //left and right floors, the inner part of the left one is ice
+        result.push(Descriptor::new(17., 1., -21.5, 2.5, ObjectType::Block));
+        result.push(Descriptor::new(10., 1., -8., 2.5, ObjectType::Ice));
         result.push(Descriptor::new(27., 1., 16.5, 2.5, ObjectType::Block));
+        //hump on the right floor, steep going up and shallow coming down
+        result.push(Descriptor::new(1., 1., 12.5, 3.5, ObjectType::SlopeUp45));
+        result.push(Descriptor::new(1., 1., 13.5, 3.5, ObjectType::Block));
+        result.push(Descriptor::new(SLOPE_22_RUN, 1., 14. + SLOPE_22_RUN / 2., 3.5, ObjectType::SlopeDown22));
+        result.push(Descriptor::new(1., 2., 12.5, 5., ObjectType::Barrel));
         result.push(Descriptor::new(4., 1., 0., 0.5, ObjectType::Block));
+        //one way platform over the middle block, can be jumped through from below
+        result.push(Descriptor::new(6., 1., 0., 3.5, ObjectType::OneWayPlatform));
         //left and right walls
         result.push(Descriptor::new(1., 23., -29.5, 5.5, ObjectType::Block));
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
@@ -201,13 +307,15 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
 
 
         //left teleporter
-        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 2));
+        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 2, "right"));
         //right teleporter
 
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 3));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 3, "left"));
 
         //bottom floor
         result.push(Descriptor::new(60., 5., 0., -14.5, ObjectType::Block));
+        //bounce pad by the left teleporter
+        result.push(Descriptor::new(2., 1., -26., -11.5, ObjectType::BouncePad));
         //block on bottom floor
         result.push(Descriptor::new(18., 4., 0., -10., ObjectType::Block));
         //innermost bottom pillars
@@ -361,8 +469,8 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 23., -29.5, 5.5, ObjectType::Block));
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
 
-        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 4));
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 1));
+        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 4, "right"));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 1, "left"));
 
 
         //bottom floor
@@ -451,8 +559,8 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         //left and right walls
         result.push(Descriptor::new(1., 23., -29.5, 5.5, ObjectType::Block));
 
-        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 1));
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 5));
+        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 1, "right"));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 5, "left"));
         
 
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
@@ -493,6 +601,7 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 2.,-4., 11., ObjectType::Barrel));
         result.push(Descriptor::new(1., 2.,4., 11., ObjectType::Barrel));
         result.push(Descriptor::new(1., 2.,23., 0., ObjectType::Barrel));
+        result.push(Descriptor::new(1., 1., -12., 10.5, ObjectType::TimePickup));
 
         
         //ceiling
@@ -504,8 +613,8 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 23., -29.5, 5.5, ObjectType::Block));
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
 
-        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 6));
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 2));
+        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 6, "right"));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 2, "left"));
         
         //bottom floor
         result.push(Descriptor::new(60., 5., 0., -14.5, ObjectType::Block));
@@ -564,8 +673,8 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 24., -29.5, 5., ObjectType::Block));
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
 
-        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 3));
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 7));
+        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 3, "right"));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 7, "right"));
         
         //bottom floor
         result.push(Descriptor::new(60., 5., 0., -14.5, ObjectType::Block));
@@ -619,8 +728,8 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 23., -29.5, 5.5, ObjectType::Block));
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
 
-        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 8));
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 4));
+        result.push(Descriptor::new3(1., 6., -29.5, -9., ObjectType::Teleporter, 8, "right"));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 4, "left"));
         
         //bottom floor
         result.push(Descriptor::new(60., 5., 0., -14.5, ObjectType::Block));
@@ -756,7 +865,7 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
 
         //left and right walls
         result.push(Descriptor::new(1., 23., -29.5, 5.5, ObjectType::Block));
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 5));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 5, "right"));
         
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
         //bottom floor
@@ -812,6 +921,7 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         result.push(Descriptor::new(1., 2., 7.,-5., ObjectType::Barrel));
         result.push(Descriptor::new(1., 2., 5.,-5., ObjectType::Barrel));
         result.push(Descriptor::new(1., 2., 4.,-5., ObjectType::Barrel));
+        result.push(Descriptor::new(1., 1., -6., 10.5, ObjectType::TimePickup));
 
 
         //ceiling
@@ -822,7 +932,7 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
         //left and right walls
         result.push(Descriptor::new(1., 32., -29.5, 0., ObjectType::Block));
         result.push(Descriptor::new(1., 23., 29.5, 5.5, ObjectType::Block));
-        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 6));
+        result.push(Descriptor::new3(1., 6., 29.5, -9., ObjectType::Teleporter, 6, "left"));
         
         //bottom floor
         result.push(Descriptor::new(60., 5., 0., -14.5, ObjectType::Block));
@@ -893,7 +1003,7 @@ pub fn get_level(id: i8) -> Vec<Descriptor> {
     result.push(Descriptor::new(1., 10., -16., -17., ObjectType::Block)); //shop sides
 
 
-    result.push(Descriptor::new3(1., 10., 15., -17., ObjectType::Teleporter, 1)); //shop sides
+    result.push(Descriptor::new3(1., 10., 15., -17., ObjectType::Teleporter, 1, "start")); //shop sides
     result.push(Descriptor::new(1., 10., 16., -17., ObjectType::Block));
 
     //result.push(Descriptor::new(32., 1., 0., -32., ObjectType::Block)); // shop box code start
@@ -942,5 +1052,126 @@ pub enum ObjectType {
     Breakable,
     Barrel,
     Credit,
+    //adds time to the run clock when touched
+    TimePickup,
+    //unlocks wall jumping when touched
+    WallJumpPickup,
     Teleporter,
+    MovingPlatform,
+    OneWayPlatform,
+    //slopes are named for the direction they go up and their angle, 45 degree slopes are 1x1 tiles
+    //22.5 degree slopes rise one tile over SLOPE_22_RUN tiles
+    SlopeUp45,
+    SlopeDown45,
+    SlopeUp22,
+    SlopeDown22,
+    Ice,
+    BouncePad,
+    Goo,
+}
+
+//how a surface affects things touching it, apply_collisions looks these up for every object it hits
+#[derive(Copy, Clone)]
+pub struct SurfaceMaterial {
+    //solid surfaces stop things, the rest can be passed through
+    pub solid: bool,
+    //how quickly horizontal speed can change while standing on it, 1 is normal ground
+    pub friction: f32,
+    //fraction of falling speed that gets turned into a bounce on landing
+    pub bounciness: f32,
+    //smallest upward speed a landing bounces back with
+    pub launch: f32,
+    //fraction of horizontal speed lost each tick while touching it
+    pub drag: f32,
+    //speed things sink through it at, for surfaces that catch things without holding them up
+    pub sink: Option<f32>,
+    //what it does to anything that can take status effects, on landing or while passing through
+    pub inflicts: Option<Effect>,
+}
+
+//how fast spikes throw things back up off them
+pub const SPIKE_BOUNCE: f32 = 8.;
+
+const GROUND: SurfaceMaterial = SurfaceMaterial {
+    solid: true,
+    friction: 1.,
+    bounciness: 0.,
+    launch: 0.,
+    drag: 0.,
+    sink: None,
+    inflicts: None,
+};
+
+const NO_SURFACE: SurfaceMaterial = SurfaceMaterial {
+    solid: false,
+    ..GROUND
+};
+
+pub fn surface_material(t: ObjectType) -> SurfaceMaterial {
+    match t {
+        ObjectType::Block
+        | ObjectType::MovingPlatform
+        | ObjectType::OneWayPlatform
+        | ObjectType::SlopeUp45
+        | ObjectType::SlopeDown45
+        | ObjectType::SlopeUp22
+        | ObjectType::SlopeDown22 => GROUND,
+        ObjectType::Ice => SurfaceMaterial {
+            friction: 0.05,
+            ..GROUND
+        },
+        ObjectType::BouncePad => SurfaceMaterial {
+            bounciness: 0.8,
+            launch: 14.,
+            ..GROUND
+        },
+        ObjectType::Goo => SurfaceMaterial {
+            drag: 0.6,
+            ..GROUND
+        },
+        ObjectType::Cobweb => SurfaceMaterial {
+            drag: 0.5,
+            sink: Some(-2.),
+            inflicts: Some(Effect::Slow),
+            ..NO_SURFACE
+        },
+        //spikes cut whatever lands on them and throw it back up
+        ObjectType::Spike => SurfaceMaterial {
+            launch: SPIKE_BOUNCE,
+            inflicts: Some(Effect::Bleed),
+            ..GROUND
+        },
+        _ => NO_SURFACE,
+    }
+}
+
+//tiles a 22.5 degree slope takes to rise one tile, 1 / tan(22.5) is 1 + sqrt(2)
+pub const SLOPE_22_RUN: f32 = 1. + std::f32::consts::SQRT_2;
+
+//rise over run of a slope tile, positive when it goes up to the right
+pub fn slope_ratio(t: ObjectType) -> Option<f32> {
+    match t {
+        ObjectType::SlopeUp45 => Some(1.),
+        ObjectType::SlopeDown45 => Some(-1.),
+        ObjectType::SlopeUp22 => Some(1. / SLOPE_22_RUN),
+        ObjectType::SlopeDown22 => Some(-1. / SLOPE_22_RUN),
+        _ => None,
+    }
+}
+
+//height of a slope's surface under a body centred at x and `half` as wide, or None if it isn't a slope or the body isn't over it
+//the body rests on the highest point of the slope under it, like it would on the corner of a block
+pub fn slope_surface(t: ObjectType, w: f32, h: f32, center: Vec3, x: f32, half: f32) -> Option<f32> {
+    let ratio = slope_ratio(t)?;
+    let left = center.x - w / 2.;
+    let right = center.x + w / 2.;
+    if x + half < left || x - half > right {
+        return None;
+    }
+    let bottom = center.y - h / 2.;
+    if ratio > 0. {
+        Some(bottom + ((x + half).min(right) - left) * ratio)
+    } else {
+        Some(bottom + (right - (x - half).max(left)) * -ratio)
+    }
 }
@@ -0,0 +1,172 @@
+use crate::physics::*;
+use bevy::prelude::*;
+
+//what the player's guns fire, each weapon uses one of these
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmoType {
+    Bullets,
+    Shells,
+    Grenades,
+    Stakes,
+}
+
+pub const AMMO_TYPES: usize = 4;
+//ammo every run starts with, the most that can be carried, and what each kill gives, in AmmoType order
+pub const START_AMMO: [u32; AMMO_TYPES] = [40, 12, 3, 6];
+pub const MAX_AMMO: [u32; AMMO_TYPES] = [99, 36, 9, 18];
+pub const KILL_AMMO: [u32; AMMO_TYPES] = [10, 3, 1, 2];
+
+pub struct Weapon {
+    pub name: &'static str,
+    //ticks between shots while L is held
+    pub fire_ticks: u32,
+    //projectiles per shot, fanned out `spread` radians apart
+    pub pellets: u32,
+    pub spread: f32,
+    pub speed: f32,
+    //how much gravity pulls on its projectiles, 1 is normal
+    pub gravity_scale: f32,
+    //times the difficulty's projectile damage, per projectile
+    pub damage: f32,
+    //enemies a projectile goes through before stopping
+    pub pierce: u32,
+    //walls a projectile bounces off before stopping
+    pub bounces: u32,
+    //blows up into shrapnel where it stops, or after this many ticks
+    pub fuse: Option<u32>,
+    pub ammo: AmmoType,
+    pub color: Color,
+    pub size: f32,
+}
+
+//in the order Q cycles through them
+pub static WEAPONS: [Weapon; 4] = [
+    Weapon {
+        name: "Pistol",
+        fire_ticks: 12,
+        pellets: 1,
+        spread: 0.,
+        speed: 15.5,
+        gravity_scale: 1.,
+        damage: 1.,
+        pierce: 0,
+        bounces: 0,
+        fuse: None,
+        ammo: AmmoType::Bullets,
+        color: Color::YELLOW,
+        size: 6.,
+    },
+    Weapon {
+        name: "Shotgun",
+        fire_ticks: 40,
+        pellets: 5,
+        spread: 0.12,
+        speed: 14.,
+        gravity_scale: 0.5,
+        damage: 0.5,
+        pierce: 0,
+        bounces: 0,
+        fuse: None,
+        ammo: AmmoType::Shells,
+        color: Color::ORANGE,
+        size: 4.,
+    },
+    Weapon {
+        name: "Grenade",
+        fire_ticks: 45,
+        pellets: 1,
+        spread: 0.,
+        speed: 10.,
+        gravity_scale: 0.6,
+        damage: 1.,
+        pierce: 0,
+        bounces: 3,
+        fuse: Some(120),
+        ammo: AmmoType::Grenades,
+        color: Color::DARK_GREEN,
+        size: 10.,
+    },
+    Weapon {
+        name: "Stake",
+        fire_ticks: 30,
+        pellets: 1,
+        spread: 0.,
+        speed: 20.,
+        gravity_scale: 0.25,
+        damage: 2.,
+        pierce: 2,
+        bounces: 0,
+        fuse: None,
+        ammo: AmmoType::Stakes,
+        color: Color::BEIGE,
+        size: 8.,
+    },
+];
+
+//how far a grenade blast breaks barrels and breakables, and the shrapnel it throws
+pub const BLAST_RADIUS: f32 = 3. * 32.;
+const SHRAPNEL_PIECES: u32 = 12;
+const SHRAPNEL_SPEED: f32 = 12.;
+
+//the player's ammo and which weapon is out
+pub struct Arsenal {
+    pub weapon: usize,
+    pub ammo: [u32; AMMO_TYPES],
+    //ticks until the weapon can fire again
+    pub cooldown: u32,
+}
+
+impl Arsenal {
+    pub fn new() -> Self {
+        Self {
+            weapon: 0,
+            ammo: START_AMMO,
+            cooldown: 0,
+        }
+    }
+    pub fn current(&self) -> &'static Weapon {
+        &WEAPONS[self.weapon]
+    }
+    pub fn next_weapon(&mut self) {
+        self.weapon = (self.weapon + 1) % WEAPONS.len();
+    }
+    pub fn ammo_left(&self) -> u32 {
+        self.ammo[self.current().ammo as usize]
+    }
+    //uses up a shot if the weapon is ready and loaded
+    pub fn fire(&mut self) -> bool {
+        if self.cooldown > 0 || self.ammo_left() == 0 {
+            return false;
+        }
+        self.ammo[self.current().ammo as usize] -= 1;
+        self.cooldown = self.current().fire_ticks;
+        true
+    }
+    pub fn add(&mut self, ammo: [u32; AMMO_TYPES]) {
+        for i in 0..AMMO_TYPES {
+            self.ammo[i] = (self.ammo[i] + ammo[i]).min(MAX_AMMO[i]);
+        }
+    }
+}
+
+//velocities of every projectile in one shot, fanned out around `aim`
+pub fn shot_velocities(weapon: &Weapon, aim: Vec2) -> Vec<Vec2> {
+    let aim = aim.normalize() * weapon.speed;
+    let middle = (weapon.pellets as f32 - 1.) / 2.;
+    (0..weapon.pellets)
+        .map(|i| rotate(aim, (i as f32 - middle) * weapon.spread))
+        .collect()
+}
+
+fn rotate(v: Vec2, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    Vec2::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+}
+
+//throws shrapnel out in every direction, the same particles an exploding barrel throws
+pub fn spawn_shrapnel(commands: &mut Commands, at: Vec3) {
+    for i in 0..SHRAPNEL_PIECES {
+        let angle = i as f32 / SHRAPNEL_PIECES as f32 * std::f32::consts::TAU;
+        spawn_particle(commands, at, rotate(Vec2::X, angle) * SHRAPNEL_SPEED, 4.);
+    }
+}
@@ -0,0 +1,53 @@
+use game::abilities::*;
+use game::active_util::*;
+
+//ticks until `done` is true, giving up after a second
+fn ticks_until(dash: &mut Dash, obj: &mut ActiveObject, done: impl Fn(&Dash) -> bool) -> u32 {
+    let mut ticks = 0;
+    while !done(dash) && ticks < 60 {
+        dash.tick(obj);
+        ticks += 1;
+    }
+    ticks
+}
+
+#[test]
+fn dash_gives_i_frames_that_outlast_it() {
+    let mut obj = ActiveObject::new();
+    let mut dash = Dash::new();
+    assert!(!dash.invulnerable());
+    dash.start(&obj);
+    assert!(dash.active() && dash.invulnerable());
+
+    let dashing = ticks_until(&mut dash, &mut obj, |d| !d.active());
+    let remaining = ticks_until(&mut dash, &mut obj, |d| !d.invulnerable());
+    assert!(dashing > 0 && remaining > 0);
+    assert!(remaining < 60);
+}
+
+#[test]
+fn dash_charge_comes_back_on_landing() {
+    let mut obj = ActiveObject::new();
+    let mut dash = Dash::new();
+    dash.start(&obj);
+    ticks_until(&mut dash, &mut obj, |d| d.cooldown == 0);
+    //still in the air, so no second dash
+    dash.start(&obj);
+    assert!(!dash.active());
+
+    obj.grounded = true;
+    dash.tick(&mut obj);
+    dash.start(&obj);
+    assert!(dash.active() && dash.invulnerable());
+}
+
+#[test]
+fn dash_goes_the_way_the_player_faces() {
+    let mut obj = ActiveObject::new();
+    obj.facing_left = true;
+    let mut dash = Dash::new();
+    dash.start(&obj);
+    dash.apply(&mut obj);
+    assert!(obj.velocity.x < 0.);
+    assert_eq!(obj.velocity.y, 0.);
+}
@@ -0,0 +1,31 @@
+use game::difficulty::*;
+
+const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Nightmare];
+
+#[test]
+fn harder_presets_are_harder() {
+    let easy = Difficulty::Easy.preset();
+    let normal = Difficulty::Normal.preset();
+    let nightmare = Difficulty::Nightmare.preset();
+    assert!(easy.enemy_health < normal.enemy_health && normal.enemy_health < nightmare.enemy_health);
+    assert!(easy.melee_damage < normal.melee_damage && normal.melee_damage < nightmare.melee_damage);
+    assert!(easy.start_time > normal.start_time && normal.start_time > nightmare.start_time);
+    assert!(easy.attack_ticks > normal.attack_ticks && normal.attack_ticks > nightmare.attack_ticks);
+}
+
+#[test]
+fn prices_scale_with_difficulty() {
+    assert_eq!(Difficulty::Normal.price(40), 40);
+    assert_eq!(Difficulty::Easy.price(40), 30);
+    assert_eq!(Difficulty::Nightmare.price(40), 60);
+}
+
+#[test]
+fn menu_cycles_through_every_difficulty() {
+    for d in ALL {
+        assert_eq!(Difficulty::from_name(&format!("{:?}", d)), Some(d));
+        assert_ne!(d.next(), d);
+        assert_eq!(d.next().next().next(), d);
+    }
+    assert_eq!(Difficulty::from_name("Hard"), None);
+}
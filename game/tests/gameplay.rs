@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use game::active_util::*;
+use game::game_state::*;
+use game::harness::*;
+use game::items::jetpack::*;
+use game::replay::*;
+use game::status_effects::*;
+use game::util::*;
+
+#[test]
+fn player_on_spawn_platform_stays_grounded() {
+    let mut h = Harness::new(1);
+    //fall onto the platform under the spawn point
+    h.step(120, &[]);
+    assert!(h.player().grounded);
+    let landed = h.player_position();
+
+    h.step(60, &[]);
+    assert!(h.player().grounded);
+    assert_eq!(h.player_position().y, landed.y);
+    assert_eq!(h.room(), 1);
+}
+
+#[test]
+fn shooting_a_breakable_breaks_it() {
+    //room 2 has a pair of movable breakables on the floor under its starting platform
+    let mut h = Harness::new(2);
+    h.step(60, &[]);
+    assert_eq!(h.count(|o| matches!(o.obj_type, ObjectType::Breakable)), 2);
+
+    //stand on that floor a few tiles to the left of them, facing them
+    h.place_player(8. * 32., 6. * 32.);
+    h.step(10, &[]);
+    assert!(h.player().grounded);
+
+    h.tap(KeyCode::L, 10);
+    h.tap(KeyCode::L, 30);
+    //the front one breaks, the one behind it is still standing
+    assert_eq!(h.count(|o| matches!(o.obj_type, ObjectType::Breakable)), 1);
+}
+
+#[test]
+fn landing_on_spikes_cuts_and_throws_back_up() {
+    let mut h = Harness::new(1);
+    h.step(10, &[]);
+    let p = h.player_position();
+    //a spike on the start platform, with the player dropped onto it from three tiles up
+    h.spawn_object(ObjectType::Spike, p.x, p.y, 32., 32.);
+    h.place_player(p.x, p.y + 96.);
+    h.step(25, &[]);
+
+    let mut query = h.app.world.query_filtered::<&StatusEffects, With<Player>>();
+    assert!(query.single(&h.app.world).has(Effect::Bleed));
+    assert!(h.player_position().y >= p.y + 32.);
+}
+
+#[test]
+fn dropped_credits_are_still_there_after_leaving_the_room() {
+    let mut h = Harness::new(1);
+    h.step(10, &[]);
+    let p = h.player_position();
+    //a credit two tiles to the right of the start platform, like a killed enemy leaves behind
+    h.spawn_object(ObjectType::Credit, p.x + 64., p.y + 64., 16., 16.);
+    h.step(1, &[]);
+
+    h.go_to_room(2);
+    assert_eq!(h.room(), 2);
+    assert_eq!(h.count(|o| matches!(o.obj_type, ObjectType::Credit)), 0);
+    h.go_to_room(1);
+    assert_eq!(h.count(|o| matches!(o.obj_type, ObjectType::Credit)), 1);
+
+    let credits = h.player_data().credits;
+    h.place_player(p.x + 64., p.y + 64.);
+    h.step(1, &[]);
+    assert_eq!(h.count(|o| matches!(o.obj_type, ObjectType::Credit)), 0);
+    assert_eq!(h.player_data().credits, credits + 50);
+}
+
+#[test]
+fn same_seed_and_input_give_same_world() {
+    let run = || {
+        let mut h = Harness::new(1);
+        h.step(30, &[KeyCode::D]);
+        h.tap(KeyCode::L, 30);
+        h.step(30, &[KeyCode::A, KeyCode::Space]);
+        h.app.world.resource::<Replay>().checksum
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn saved_unlocks_carry_into_a_new_game() {
+    let mut progress = Progress::new();
+    progress.unlock(JETPACK);
+    let mut h = Harness::with_progress(1, progress);
+    assert!(h.player_data().items.contains(&JETPACK));
+}
@@ -0,0 +1,70 @@
+use bevy::ecs::event::Events;
+use bevy::prelude::*;
+use game::health::*;
+use game::status_effects::*;
+
+#[test]
+fn armor_and_resistances_soften_hits() {
+    let mut health = Health::new(100);
+    assert_eq!(health.absorb(10, DamageKind::Melee), 10);
+    health.resistances[DamageKind::Explosion as usize] = 0.5;
+    assert_eq!(health.absorb(10, DamageKind::Explosion), 5);
+    health.armor = 3;
+    assert_eq!(health.absorb(10, DamageKind::Melee), 7);
+    assert_eq!(health.absorb(2, DamageKind::Melee), 0);
+}
+
+fn damage_app() -> App {
+    let mut app = App::new();
+    app.add_event::<DamageEvent>()
+        .add_event::<DeathEvent>()
+        .add_system(apply_damage);
+    app
+}
+
+fn hit(app: &mut App, target: Entity, amount: i32, kind: DamageKind) {
+    app.world.resource_mut::<Events<DamageEvent>>().send(DamageEvent {
+        target: target,
+        amount: amount,
+        source: None,
+        kind: kind,
+    });
+    app.update();
+}
+
+fn deaths(app: &App) -> Vec<Entity> {
+    let events = app.world.resource::<Events<DeathEvent>>();
+    events.get_reader().iter(events).map(|d| d.entity).collect()
+}
+
+#[test]
+fn running_out_of_health_sends_one_death() {
+    let mut app = damage_app();
+    let target = app.world.spawn().insert(Health::new(30)).id();
+
+    hit(&mut app, target, 20, DamageKind::Melee);
+    assert_eq!(app.world.get::<Health>(target).unwrap().current, 10);
+    assert!(deaths(&app).is_empty());
+
+    hit(&mut app, target, 20, DamageKind::Projectile);
+    assert!(!app.world.get::<Health>(target).unwrap().alive());
+    assert_eq!(deaths(&app), vec![target]);
+
+    //hitting something already dead doesn't kill it again
+    app.update();
+    hit(&mut app, target, 20, DamageKind::Melee);
+    assert!(deaths(&app).is_empty());
+}
+
+#[test]
+fn status_effects_weaken_all_but_status_damage() {
+    let mut app = damage_app();
+    let mut effects = StatusEffects::new();
+    effects.add(Effect::Poison);
+    let target = app.world.spawn().insert(Health::new(100)).insert(effects).id();
+
+    hit(&mut app, target, 10, DamageKind::Status);
+    assert_eq!(app.world.get::<Health>(target).unwrap().current, 90);
+    hit(&mut app, target, 10, DamageKind::Melee);
+    assert!(app.world.get::<Health>(target).unwrap().current < 80);
+}
@@ -0,0 +1,36 @@
+use game::active_util::*;
+use game::fixed_step::*;
+use game::items::no_item::*;
+use game::items::*;
+
+#[test]
+fn every_item_is_found_by_its_name() {
+    for item in ITEMS {
+        assert_eq!(find_item(item.name()).unwrap().name(), item.name());
+    }
+    assert!(find_item("Rocket").is_none());
+}
+
+#[test]
+fn item_names_are_unique() {
+    for (i, a) in ITEMS.iter().enumerate() {
+        for b in &ITEMS[i + 1..] {
+            assert_ne!(a.name(), b.name());
+        }
+    }
+}
+
+#[test]
+fn shop_sells_only_priced_items() {
+    assert!(items_for_sale().all(|item| item.price().is_some()));
+    assert!(items_for_sale().all(|item| item.name() != NO_ITEM));
+}
+
+#[test]
+fn no_item_jumps_plainly() {
+    let mut pl = ActiveObject::new();
+    let mut p = Player::new();
+    pl.grounded = true;
+    find_item(NO_ITEM).unwrap().on_activate(&mut pl, &mut p, &TickInput::new());
+    assert_eq!(pl.velocity.y, JUMP_SPEED);
+}
@@ -0,0 +1,40 @@
+use game::melee::*;
+
+fn wait(combo: &mut Combo, ticks: u32) {
+    for _ in 0..ticks {
+        combo.tick();
+    }
+}
+
+#[test]
+fn swings_wait_out_their_recovery() {
+    let mut combo = Combo::new();
+    assert!(combo.swing().is_some());
+    wait(&mut combo, COMBO[0].recovery - 1);
+    assert!(combo.swing().is_none());
+    wait(&mut combo, 1);
+    assert!(combo.swing().is_some());
+}
+
+#[test]
+fn swinging_inside_the_window_moves_through_the_combo() {
+    let mut combo = Combo::new();
+    for step in COMBO.iter() {
+        let swing = combo.swing().unwrap();
+        assert!(std::ptr::eq(swing, step));
+        wait(&mut combo, step.recovery);
+    }
+    //the last step ends the combo
+    assert!(std::ptr::eq(combo.swing().unwrap(), &COMBO[0]));
+}
+
+#[test]
+fn missing_the_window_starts_the_combo_over() {
+    let mut combo = Combo::new();
+    combo.swing();
+    wait(&mut combo, COMBO[0].recovery + COMBO[0].window - 1);
+    assert_eq!(combo.step, 1);
+    wait(&mut combo, 1);
+    assert_eq!(combo.step, 0);
+    assert!(std::ptr::eq(combo.swing().unwrap(), &COMBO[0]));
+}
@@ -0,0 +1,34 @@
+use game::movement_mesh::*;
+use game::util::*;
+
+//a vertex on the floor and a ledge four tiles up and two to the left, too high for a plain jump
+fn ledge_graph() -> Graph {
+    let mut graph = Graph::new();
+    graph.vertices.push(Vertex::new(0., 16., 0));
+    graph.vertices.push(Vertex::new(-64., 144., 1));
+    graph
+}
+
+#[test]
+fn walls_next_to_high_ledges_get_wall_jump_edges() {
+    let mut graph = ledge_graph();
+    //a wall one tile to the right of the floor vertex, reaching past the ledge
+    let wall = Descriptor::new2(32., 400., 64., 100., ObjectType::Block, 0);
+    add_wall_jump_edges(&mut graph, &[wall]);
+    assert_eq!(graph.edges[0][1].path, Motion::WallJumpLeft);
+    assert_eq!(graph.edges[1][0].path, Motion::Stop);
+}
+
+#[test]
+fn no_wall_means_no_wall_jump() {
+    let mut graph = ledge_graph();
+    //too far away to reach before the jump peaks
+    let wall = Descriptor::new2(32., 400., 400., 100., ObjectType::Block, 0);
+    add_wall_jump_edges(&mut graph, &[wall]);
+    assert_eq!(graph.edges[0][1].path, Motion::Stop);
+}
+
+#[test]
+fn room_1_walk_edges_are_walkable() {
+    assert_eq!(check_walk_edges(&get_level_mesh(1), &get_level(1)), vec![]);
+}
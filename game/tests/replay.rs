@@ -0,0 +1,18 @@
+use game::replay::*;
+
+#[test]
+fn recordings_keep_the_unlocks_they_started_with() {
+    let mut replay = Replay::new(7, 1);
+    replay.unlocks = vec!["Jetpack".to_string(), "WallJump".to_string()];
+    replay.frames = vec![0, 3, 1];
+    let loaded = Replay::parse(&replay.to_text()).unwrap();
+    assert_eq!(loaded.unlocks, replay.unlocks);
+    assert_eq!(loaded.frames, replay.frames);
+    assert!(matches!(loaded.mode, ReplayMode::Replay));
+}
+
+#[test]
+fn recordings_without_unlocks_start_with_none() {
+    let loaded = Replay::parse(&Replay::new(7, 1).to_text()).unwrap();
+    assert!(loaded.unlocks.is_empty());
+}
@@ -0,0 +1,47 @@
+use game::difficulty::*;
+use game::items::no_item::*;
+use game::save::*;
+
+fn sample() -> SaveData {
+    SaveData {
+        credits: 75,
+        health: 60,
+        items: vec![NO_ITEM, "Jetpack"],
+        active_item: 1,
+        room: 3,
+        discovered: vec![1, 2, 3],
+        unlocks: vec!["Jetpack".to_string()],
+        difficulty: Difficulty::Nightmare,
+    }
+}
+
+#[test]
+fn save_survives_a_round_trip() {
+    let data = SaveData::parse(&sample().to_text()).unwrap();
+    assert_eq!(data.credits, 75);
+    assert_eq!(data.health, 60);
+    assert_eq!(data.items, vec![NO_ITEM, "Jetpack"]);
+    assert_eq!(data.active_item, 1);
+    assert_eq!(data.room, 3);
+    assert_eq!(data.discovered, vec![1, 2, 3]);
+    assert_eq!(data.unlocks, vec!["Jetpack".to_string()]);
+    assert_eq!(data.difficulty, Difficulty::Nightmare);
+}
+
+#[test]
+fn saves_from_before_difficulties_load_as_normal() {
+    let text = "version 1\ncredits 10\nhealth 100\nitems None\nactive_item 0\nroom 1\ndiscovered 1\nunlocks \n";
+    let data = SaveData::parse(text).unwrap();
+    assert_eq!(data.difficulty, Difficulty::Normal);
+    assert_eq!(data.credits, 10);
+    //and they get written back in the current format
+    assert!(data.to_text().starts_with(&format!("version {}\n", SAVE_VERSION)));
+}
+
+#[test]
+fn bad_saves_are_rejected() {
+    assert!(SaveData::parse("credits 10\n").is_err());
+    assert!(SaveData::parse(&format!("version {}\n", SAVE_VERSION + 1)).is_err());
+    let unknown_item = sample().to_text().replace("Jetpack", "Rocket");
+    assert!(SaveData::parse(&unknown_item).is_err());
+}
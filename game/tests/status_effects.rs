@@ -0,0 +1,70 @@
+use game::status_effects::*;
+
+#[test]
+fn stacks_stop_at_the_limit() {
+    let mut effects = StatusEffects::new();
+    let max = Effect::Poison.info().max_stacks;
+    for _ in 0..max + 3 {
+        effects.add(Effect::Poison);
+    }
+    assert_eq!(effects.stacks(Effect::Poison), max);
+    assert_eq!(effects.stacks(Effect::Burn), 0);
+}
+
+#[test]
+fn ticks_deal_damage_per_stack_every_interval() {
+    let mut effects = StatusEffects::new();
+    effects.add(Effect::Burn);
+    effects.add(Effect::Burn);
+    let info = Effect::Burn.info();
+    let mut dealt = Vec::new();
+    for _ in 0..info.interval {
+        dealt.push(effects.tick());
+    }
+    assert_eq!(dealt.iter().sum::<i32>(), info.damage * 2);
+    assert_eq!(*dealt.last().unwrap(), info.damage * 2);
+}
+
+#[test]
+fn effects_wear_off() {
+    let mut effects = StatusEffects::new();
+    effects.add(Effect::Stun);
+    assert!(effects.stunned());
+    for _ in 0..Effect::Stun.info().ticks {
+        effects.tick();
+    }
+    assert!(!effects.stunned());
+}
+
+#[test]
+fn adding_a_stack_starts_the_duration_over() {
+    let mut effects = StatusEffects::new();
+    let ticks = Effect::Bleed.info().ticks;
+    effects.add(Effect::Bleed);
+    for _ in 0..ticks - 1 {
+        effects.tick();
+    }
+    effects.add(Effect::Bleed);
+    for _ in 0..ticks - 1 {
+        effects.tick();
+    }
+    assert_eq!(effects.stacks(Effect::Bleed), 2);
+}
+
+#[test]
+fn keeping_an_effect_does_not_stack_it() {
+    let mut effects = StatusEffects::new();
+    for _ in 0..10 {
+        effects.keep(Effect::Slow);
+    }
+    assert_eq!(effects.stacks(Effect::Slow), 1);
+    assert_eq!(effects.speed_scale(), 1. - Effect::Slow.info().slow);
+}
+
+#[test]
+fn poison_makes_hits_hurt_more() {
+    let mut effects = StatusEffects::new();
+    assert_eq!(effects.hurt(20), 20);
+    effects.add(Effect::Poison);
+    assert!(effects.hurt(20) > 20);
+}